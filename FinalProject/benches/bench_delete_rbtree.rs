@@ -0,0 +1,50 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use FinalProject::arena_rbtree::ArenaRBTree;
+use FinalProject::rbtree::RBTree;
+
+// keys are deleted in a scattered order (7919 is prime, so this visits every
+// key once) to exercise all the fix-up cases rather than only the leftmost path
+fn scattered(i: i32, size: i32) -> i32 {
+    ((i as i64 * 7919) % size as i64) as i32
+}
+
+fn bench_delete_rbtree(c: &mut Criterion) {
+    let tree_sizes = [10_000, 40_000, 70_000, 100_000, 130_000];
+
+    for &size in &tree_sizes {
+        c.bench_function(&format!("rbTree_delete for {} elements", size), |b| {
+            b.iter_batched(
+                || (0..size).collect::<RBTree<i32>>(),
+                |mut rbtree| {
+                    // Deleting every element
+                    for i in 0..size {
+                        rbtree.delete(black_box(&scattered(i, size))).unwrap();
+                    }
+                },
+                BatchSize::LargeInput,
+            );
+        });
+    }
+}
+
+fn bench_delete_arena_rbtree(c: &mut Criterion) {
+    let tree_sizes = [10_000, 40_000, 70_000, 100_000, 130_000];
+
+    for &size in &tree_sizes {
+        c.bench_function(&format!("arenaRBTree_delete for {} elements", size), |b| {
+            b.iter_batched(
+                || (0..size).collect::<ArenaRBTree<i32>>(),
+                |mut rbtree| {
+                    // Deleting every element
+                    for i in 0..size {
+                        rbtree.delete(black_box(&scattered(i, size))).unwrap();
+                    }
+                },
+                BatchSize::LargeInput,
+            );
+        });
+    }
+}
+
+criterion_group!(benches, bench_delete_rbtree, bench_delete_arena_rbtree);
+criterion_main!(benches);
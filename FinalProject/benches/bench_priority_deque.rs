@@ -0,0 +1,47 @@
+use std::collections::BinaryHeap;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use FinalProject::priority_deque::{AVLPriorityDeque, RBPriorityDeque};
+
+// priorities arrive in a scattered order and are drawn from only size / 4
+// values, so each one is pushed about four times: a duplicate-heavy load
+// that keeps many equal priorities queued at once
+fn scattered(i: i32, size: i32) -> i32 {
+    ((i as i64 * 7919) % (size as i64 / 4)) as i32
+}
+
+// the same mixed workload for every queue: two pushes for each pop of the
+// largest item, then draining what is left
+macro_rules! mixed_workload {
+    ($queue:expr, $size:expr, $pop:ident) => {{
+        let mut queue = $queue;
+        for i in 0..$size {
+            queue.push(black_box(scattered(i, $size)));
+            if i % 2 == 1 {
+                black_box(queue.$pop());
+            }
+        }
+        while let Some(item) = queue.$pop() {
+            black_box(item);
+        }
+    }};
+}
+
+fn bench_priority_deque(c: &mut Criterion) {
+    let sizes = [10_000, 40_000, 70_000, 100_000, 130_000];
+
+    for &size in &sizes {
+        c.bench_function(&format!("binaryHeap_mixed for {} elements", size), |b| {
+            b.iter(|| mixed_workload!(BinaryHeap::new(), size, pop))
+        });
+        c.bench_function(&format!("rbPriorityDeque_mixed for {} elements", size), |b| {
+            b.iter(|| mixed_workload!(RBPriorityDeque::new(), size, pop_max))
+        });
+        c.bench_function(&format!("avlPriorityDeque_mixed for {} elements", size), |b| {
+            b.iter(|| mixed_workload!(AVLPriorityDeque::new(), size, pop_max))
+        });
+    }
+}
+
+criterion_group!(benches, bench_priority_deque);
+criterion_main!(benches);
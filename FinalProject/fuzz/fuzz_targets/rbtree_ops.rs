@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use FinalProject::rbtree::RBTree;
+use FinalProject_fuzz::{run, Op};
+
+fuzz_target!(|ops: Vec<Op>| {
+    run(RBTree::new(), &ops);
+});
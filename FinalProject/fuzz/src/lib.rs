@@ -0,0 +1,37 @@
+#![allow(non_snake_case)]
+
+use std::collections::BTreeSet;
+
+use arbitrary::Arbitrary;
+use FinalProject::ordered_set::OrderedSet;
+
+/// One step of a fuzzed workload. Keys are `u8` so that removes and repeated
+/// inserts regularly hit keys already in the tree.
+#[derive(Arbitrary, Clone, Copy, Debug)]
+pub enum Op {
+    Insert(u8),
+    Remove(u8),
+    Contains(u8),
+    Min,
+    Max,
+}
+
+/// Applies `ops` to `set` and to a `BTreeSet`, panicking on the first result
+/// that differs or on any broken tree invariant.
+pub fn run<S: OrderedSet<u8>>(mut set: S, ops: &[Op]) {
+    let mut model = BTreeSet::new();
+    for op in ops {
+        match *op {
+            Op::Insert(key) => assert_eq!(set.insert(key), model.insert(key), "{:?}", op),
+            Op::Remove(key) => assert_eq!(set.remove(&key), model.remove(&key), "{:?}", op),
+            Op::Contains(key) => assert_eq!(set.contains(&key), model.contains(&key), "{:?}", op),
+            Op::Min => assert_eq!(set.min(), model.first().copied()),
+            Op::Max => assert_eq!(set.max(), model.last().copied()),
+        }
+        if let Err(violation) = set.validate() {
+            panic!("invariant broken after {:?}: {}", op, violation);
+        }
+        assert_eq!(set.len(), model.len());
+    }
+    assert!(set.inorder_traversal().into_iter().eq(model.into_iter()));
+}
@@ -0,0 +1,59 @@
+use std::ops::{Index, IndexMut};
+
+// Index of a node inside an `Arena`. `NIL` marks a missing child or parent.
+pub(crate) type NodeId = u32;
+pub(crate) const NIL: NodeId = u32::MAX;
+
+// Node storage for the arena-backed trees. Nodes are addressed by `u32`
+// index instead of `Rc`, and freed slots are kept on a free list for reuse,
+// so a tree that shrinks and grows again does not reallocate.
+#[derive(Clone, Debug)]
+pub(crate) struct Arena<N> {
+    nodes: Vec<Option<N>>,
+    free: Vec<NodeId>,
+}
+
+impl<N> Arena<N> {
+    pub(crate) fn new() -> Self {
+        Arena { nodes: Vec::new(), free: Vec::new() }
+    }
+
+    pub(crate) fn alloc(&mut self, node: N) -> NodeId {
+        match self.free.pop() {
+            Some(id) => {
+                self.nodes[id as usize] = Some(node);
+                id
+            },
+            None => {
+                assert!(self.nodes.len() < NIL as usize, "arena is full");
+                self.nodes.push(Some(node));
+                (self.nodes.len() - 1) as NodeId
+            }
+        }
+    }
+
+    pub(crate) fn release(&mut self, id: NodeId) -> N {
+        let node = self.nodes[id as usize].take().expect("arena slot is vacant");
+        self.free.push(id);
+        node
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.nodes.clear();
+        self.free.clear();
+    }
+}
+
+impl<N> Index<NodeId> for Arena<N> {
+    type Output = N;
+
+    fn index(&self, id: NodeId) -> &N {
+        self.nodes[id as usize].as_ref().expect("arena slot is vacant")
+    }
+}
+
+impl<N> IndexMut<NodeId> for Arena<N> {
+    fn index_mut(&mut self, id: NodeId) -> &mut N {
+        self.nodes[id as usize].as_mut().expect("arena slot is vacant")
+    }
+}
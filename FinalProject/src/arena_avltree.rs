@@ -0,0 +1,920 @@
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::fmt::{Debug, Display};
+use std::ops::{Bound, RangeBounds};
+
+use crate::arena::{Arena, NodeId, NIL};
+use crate::error::TreeError;
+use crate::invariant::InvariantViolation;
+use crate::ordered_set::{OrderedSet, PrintableSet};
+use crate::set_ops::{Difference, Intersection, SymmetricDifference, Union};
+
+#[cfg(feature = "debug_print")]
+macro_rules! debug_println {
+    ($($args:tt)*)=>{
+        println!($($args)*);
+    };
+}
+
+#[cfg(not(feature = "debug_print"))]
+macro_rules! debug_println {
+    ($($args:tt)*)=>{};
+}
+
+#[derive(Debug, Clone)]
+struct Node<T> {
+    value: T,
+    left: NodeId,
+    right: NodeId,
+    height: isize,
+    // number of nodes in the subtree rooted here
+    size: usize,
+}
+
+impl<T> Node<T> {
+    fn new(value: T) -> Self {
+        Node {
+            value,
+            left: NIL,
+            right: NIL,
+            height: 1,
+            size: 1,
+        }
+    }
+}
+
+/// `AVLTree` with its nodes kept in a `Vec` arena and linked by `u32` index
+/// instead of `Rc<RefCell<...>>`, so walking the tree needs no reference
+/// counting or runtime borrow checks.
+///
+/// It mirrors the core of `AVLTree`: insertion and deletion, iterators and
+/// ranges, order statistics, nearest-value queries, split/append, set
+/// algebra, `validate` and `OrderedSet`. Values always use their own `Ord`
+/// and are looked up as `&T` rather than by borrowed form, and there
+/// are no cursors, `first`/`last`, `pop_*`, `retain` or `drain*` methods.
+#[derive(Debug, Clone)]
+pub struct ArenaAVLTree<T: Ord + Clone> {
+    nodes: Arena<Node<T>>,
+    root: NodeId,
+}
+
+impl<T: Ord + Clone> Default for ArenaAVLTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// structural helpers, shared with the iterators
+impl<T: Ord + Clone> ArenaAVLTree<T> {
+    fn height_of(&self, id: NodeId) -> isize {
+        if id == NIL { 0 } else { self.nodes[id].height }
+    }
+
+    fn size_of(&self, id: NodeId) -> usize {
+        if id == NIL { 0 } else { self.nodes[id].size }
+    }
+
+    fn balance_factor(&self, id: NodeId) -> isize {
+        self.height_of(self.nodes[id].left) - self.height_of(self.nodes[id].right)
+    }
+
+    // recomputes height and size from the children
+    fn update(&mut self, id: NodeId) {
+        let (left, right) = (self.nodes[id].left, self.nodes[id].right);
+        let height = 1 + std::cmp::max(self.height_of(left), self.height_of(right));
+        let size = 1 + self.size_of(left) + self.size_of(right);
+        let node = &mut self.nodes[id];
+        node.height = height;
+        node.size = size;
+    }
+
+    fn rotate_left(&mut self, x: NodeId) -> NodeId {
+        debug_println!("Rotating left");
+        let y = self.nodes[x].right;
+        debug_assert!(y != NIL, "rotate_left requires a right child");
+        self.nodes[x].right = self.nodes[y].left;
+        self.nodes[y].left = x;
+        self.update(x);
+        self.update(y);
+        y
+    }
+
+    fn rotate_right(&mut self, y: NodeId) -> NodeId {
+        debug_println!("Rotating right");
+        let x = self.nodes[y].left;
+        debug_assert!(x != NIL, "rotate_right requires a left child");
+        self.nodes[y].left = self.nodes[x].right;
+        self.nodes[x].right = y;
+        self.update(y);
+        self.update(x);
+        x
+    }
+
+    // updates `id` and restores its balance, returning the new subtree root
+    fn balance(&mut self, id: NodeId) -> NodeId {
+        self.update(id);
+        let balance_factor = self.balance_factor(id);
+
+        // Left heavy
+        if balance_factor > 1 {
+            let left = self.nodes[id].left;
+            if self.balance_factor(left) < 0 {
+                self.nodes[id].left = self.rotate_left(left);
+            }
+            return self.rotate_right(id);
+        }
+
+        // Right heavy
+        if balance_factor < -1 {
+            let right = self.nodes[id].right;
+            if self.balance_factor(right) > 0 {
+                self.nodes[id].right = self.rotate_right(right);
+            }
+            return self.rotate_left(id);
+        }
+
+        id
+    }
+
+    // returns the new subtree root and whether `value` was added
+    fn insert_rec(&mut self, id: NodeId, value: T) -> (NodeId, bool) {
+        if id == NIL {
+            return (self.nodes.alloc(Node::new(value)), true);
+        }
+        match value.cmp(&self.nodes[id].value) {
+            Ordering::Less => {
+                let left = self.nodes[id].left;
+                let (left, inserted) = self.insert_rec(left, value);
+                self.nodes[id].left = left;
+                if !inserted {
+                    return (id, false);
+                }
+            },
+            Ordering::Greater => {
+                let right = self.nodes[id].right;
+                let (right, inserted) = self.insert_rec(right, value);
+                self.nodes[id].right = right;
+                if !inserted {
+                    return (id, false);
+                }
+            },
+            Ordering::Equal => return (id, false),
+        }
+        (self.balance(id), true)
+    }
+
+    // returns the new subtree root and the removed value, if it was found
+    fn delete_rec(&mut self, id: NodeId, value: &T) -> (NodeId, Option<T>) {
+        if id == NIL {
+            return (NIL, None);
+        }
+        let removed = match value.cmp(&self.nodes[id].value) {
+            Ordering::Less => {
+                let (left, removed) = self.delete_rec(self.nodes[id].left, value);
+                self.nodes[id].left = left;
+                removed
+            },
+            Ordering::Greater => {
+                let (right, removed) = self.delete_rec(self.nodes[id].right, value);
+                self.nodes[id].right = right;
+                removed
+            },
+            Ordering::Equal => {
+                let (left, right) = (self.nodes[id].left, self.nodes[id].right);
+                // the in-order successor takes the place of the removed node
+                let replacement = if left == NIL {
+                    right
+                } else if right == NIL {
+                    left
+                } else {
+                    let (rest, successor) = self.remove_min_rec(right);
+                    self.nodes[successor].left = left;
+                    self.nodes[successor].right = rest;
+                    self.balance(successor)
+                };
+                return (replacement, Some(self.nodes.release(id).value));
+            }
+        };
+        match removed {
+            Some(_) => (self.balance(id), removed),
+            None => (id, None),
+        }
+    }
+
+    // detaches the leftmost node of the subtree, returning the rebalanced rest
+    fn remove_min_rec(&mut self, id: NodeId) -> (NodeId, NodeId) {
+        let left = self.nodes[id].left;
+        if left == NIL {
+            let rest = self.nodes[id].right;
+            self.nodes[id].right = NIL;
+            self.update(id);
+            return (rest, id);
+        }
+        let (rest, min) = self.remove_min_rec(left);
+        self.nodes[id].left = rest;
+        (self.balance(id), min)
+    }
+
+    // builds a subtree of `count` nodes taking values from `values` in order,
+    // so both sides differ in size by at most one
+    fn build_balanced<I: Iterator<Item = T>>(&mut self, count: usize, values: &mut I) -> NodeId {
+        if count == 0 {
+            return NIL;
+        }
+        let left_count = (count - 1) / 2;
+        let left = self.build_balanced(left_count, values);
+        let id = self.nodes.alloc(Node::new(values.next().expect("fewer values than count")));
+        let right = self.build_balanced(count - 1 - left_count, values);
+        self.nodes[id].left = left;
+        self.nodes[id].right = right;
+        self.update(id);
+        id
+    }
+
+    // ids of the subtree in order
+    fn inorder_ids(&self, id: NodeId, result: &mut Vec<NodeId>) {
+        if id != NIL {
+            self.inorder_ids(self.nodes[id].left, result);
+            result.push(id);
+            self.inorder_ids(self.nodes[id].right, result);
+        }
+    }
+
+    // moves every value out in order, leaving the tree empty
+    fn take_values(&mut self) -> Vec<T> {
+        let mut ids = Vec::with_capacity(self.size_of(self.root));
+        self.inorder_ids(self.root, &mut ids);
+        let values = ids.into_iter().map(|id| self.nodes.release(id).value).collect();
+        self.nodes.clear();
+        self.root = NIL;
+        values
+    }
+}
+
+impl<T: Ord + Clone> ArenaAVLTree<T> {
+    pub fn new() -> Self {
+        ArenaAVLTree { nodes: Arena::new(), root: NIL }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root == NIL
+    }
+
+    // builds a perfectly balanced tree in O(n) from values in ascending
+    // order; repeated values are kept once
+    pub fn from_sorted_vec(mut values: Vec<T>) -> Self {
+        values.dedup();
+        debug_assert!(values.windows(2).all(|w| w[0] < w[1]), "from_sorted_vec requires sorted input");
+        let mut tree = Self::new();
+        let count = values.len();
+        tree.root = tree.build_balanced(count, &mut values.into_iter());
+        tree
+    }
+
+    pub fn from_sorted_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from_sorted_vec(iter.into_iter().collect())
+    }
+
+    // returns false if the value was already present
+    pub fn insert(&mut self, value: T) -> bool {
+        let (root, inserted) = self.insert_rec(self.root, value);
+        self.root = root;
+        inserted
+    }
+
+    // removes `value`, returning the value that was stored
+    pub fn delete(&mut self, value: &T) -> Result<T, TreeError> {
+        let (root, removed) = self.delete_rec(self.root, value);
+        self.root = root;
+        removed.ok_or(TreeError::KeyNotFound)
+    }
+
+    // splits the tree in two at `value`: self keeps the values less than it
+    // and the returned tree gets the rest. Unlike `AVLTree::split_off`,
+    // which cuts the tree in O(log n), this is O(n): the moved nodes need an
+    // arena of their own, so both sides are rebuilt.
+    pub fn split_off(&mut self, value: &T) -> Self {
+        let mut values = self.take_values();
+        let greater = values.split_off(values.partition_point(|v| v < value));
+        *self = Self::from_sorted_vec(values);
+        Self::from_sorted_vec(greater)
+    }
+
+    // moves every value of `other` into self, leaving `other` empty. Unlike
+    // `AVLTree::append`, which joins trees with disjoint ranges in
+    // O(log n), this is always O(n + m): the nodes of `other` have to move
+    // into this arena anyway, so both trees are merged and rebuilt.
+    pub fn append(&mut self, other: &mut Self) {
+        if other.is_empty() {
+            return;
+        }
+        if self.is_empty() {
+            std::mem::swap(self, other);
+            return;
+        }
+        let values = self.take_values();
+        let other_values = other.take_values();
+        *self = Self::from_sorted_iter(Union::new(values.into_iter(), other_values.into_iter()));
+    }
+
+    fn min_id(&self) -> NodeId {
+        let mut id = self.root;
+        while id != NIL && self.nodes[id].left != NIL {
+            id = self.nodes[id].left;
+        }
+        id
+    }
+
+    fn max_id(&self) -> NodeId {
+        let mut id = self.root;
+        while id != NIL && self.nodes[id].right != NIL {
+            id = self.nodes[id].right;
+        }
+        id
+    }
+
+    pub fn min(&self) -> Option<T> {
+        let id = self.min_id();
+        (id != NIL).then(|| self.nodes[id].value.clone())
+    }
+
+    pub fn max(&self) -> Option<T> {
+        let id = self.max_id();
+        (id != NIL).then(|| self.nodes[id].value.clone())
+    }
+
+    pub fn count_leaves(&self) -> usize {
+        self.count_leaves_rec(self.root)
+    }
+
+    fn count_leaves_rec(&self, id: NodeId) -> usize {
+        if id == NIL {
+            return 0;
+        }
+        let node = &self.nodes[id];
+        if node.left == NIL && node.right == NIL {
+            return 1;
+        }
+        self.count_leaves_rec(node.left) + self.count_leaves_rec(node.right)
+    }
+
+    pub fn inorder_traversal(&self) -> Vec<T> {
+        self.iter().collect()
+    }
+
+    pub fn height(&self) -> isize {
+        self.height_of(self.root)
+    }
+
+    fn find(&self, value: &T) -> NodeId {
+        let mut id = self.root;
+        while id != NIL {
+            match value.cmp(&self.nodes[id].value) {
+                Ordering::Less => id = self.nodes[id].left,
+                Ordering::Greater => id = self.nodes[id].right,
+                Ordering::Equal => break,
+            }
+        }
+        id
+    }
+
+    pub fn search(&self, value: &T) -> bool {
+        self.find(value) != NIL
+    }
+
+    // returns the stored value equal to `value`, if any
+    pub fn get(&self, value: &T) -> Option<T> {
+        let id = self.find(value);
+        (id != NIL).then(|| self.nodes[id].value.clone())
+    }
+
+    // lazy in-order iterator, see `Iter`
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { range: Range::new(self, Bound::Unbounded, Bound::Unbounded) }
+    }
+
+    // lazy in-order iterator over the values within `range`
+    pub fn range<R: RangeBounds<T>>(&self, range: R) -> Range<'_, T> {
+        Range::new(self, range.start_bound().cloned(), range.end_bound().cloned())
+    }
+
+    // number of values within `range`, in O(log n) using subtree sizes
+    pub fn count_range<R: RangeBounds<T>>(&self, range: R) -> usize {
+        let upper = match range.end_bound() {
+            Bound::Included(upper) => self.count_below(upper, true),
+            Bound::Excluded(upper) => self.count_below(upper, false),
+            Bound::Unbounded => self.count() as usize,
+        };
+        let lower = match range.start_bound() {
+            Bound::Included(lower) => self.count_below(lower, false),
+            Bound::Excluded(lower) => self.count_below(lower, true),
+            Bound::Unbounded => 0,
+        };
+        upper.saturating_sub(lower)
+    }
+
+    // largest value less than or equal to `value`
+    pub fn floor(&self, value: &T) -> Option<T> {
+        self.range((Bound::Unbounded, Bound::Included(value.clone()))).next_back()
+    }
+
+    // smallest value greater than or equal to `value`
+    pub fn ceiling(&self, value: &T) -> Option<T> {
+        self.range((Bound::Included(value.clone()), Bound::Unbounded)).next()
+    }
+
+    // largest value strictly less than `value`
+    pub fn predecessor(&self, value: &T) -> Option<T> {
+        self.range((Bound::Unbounded, Bound::Excluded(value.clone()))).next_back()
+    }
+
+    // smallest value strictly greater than `value`
+    pub fn successor(&self, value: &T) -> Option<T> {
+        self.range((Bound::Excluded(value.clone()), Bound::Unbounded)).next()
+    }
+
+    // lazy iterator over the values in self or `other`
+    pub fn union<'a>(&'a self, other: &'a Self) -> Union<Iter<'a, T>> {
+        Union::new(self.iter(), other.iter())
+    }
+
+    // lazy iterator over the values in both self and `other`
+    pub fn intersection<'a>(&'a self, other: &'a Self) -> Intersection<Iter<'a, T>> {
+        Intersection::new(self.iter(), other.iter())
+    }
+
+    // lazy iterator over the values in self but not in `other`
+    pub fn difference<'a>(&'a self, other: &'a Self) -> Difference<Iter<'a, T>> {
+        Difference::new(self.iter(), other.iter())
+    }
+
+    // lazy iterator over the values in exactly one of self and `other`
+    pub fn symmetric_difference<'a>(&'a self, other: &'a Self) -> SymmetricDifference<Iter<'a, T>> {
+        SymmetricDifference::new(self.iter(), other.iter())
+    }
+
+    // new tree holding the union of self and `other`
+    pub fn union_tree(&self, other: &Self) -> Self {
+        Self::from_sorted_iter(self.union(other))
+    }
+
+    // new tree holding the intersection of self and `other`
+    pub fn intersection_tree(&self, other: &Self) -> Self {
+        Self::from_sorted_iter(self.intersection(other))
+    }
+
+    // new tree holding the values of self that are not in `other`
+    pub fn difference_tree(&self, other: &Self) -> Self {
+        Self::from_sorted_iter(self.difference(other))
+    }
+
+    // new tree holding the symmetric difference of self and `other`
+    pub fn symmetric_difference_tree(&self, other: &Self) -> Self {
+        Self::from_sorted_iter(self.symmetric_difference(other))
+    }
+
+    // true if every value of self is also in `other`
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.count() <= other.count() && self.difference(other).next().is_none()
+    }
+
+    // true if every value of `other` is also in self
+    pub fn is_superset(&self, other: &Self) -> bool {
+        other.is_subset(self)
+    }
+
+    // true if self and `other` have no value in common
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.intersection(other).next().is_none()
+    }
+
+    pub fn print_preorder(&self) -> Vec<T> {
+        let mut result = Vec::new();
+        let mut stack = vec![self.root];
+        while let Some(id) = stack.pop() {
+            if id != NIL {
+                result.push(self.nodes[id].value.clone());
+                stack.push(self.nodes[id].right);
+                stack.push(self.nodes[id].left);
+            }
+        }
+        result
+    }
+
+    pub fn print_levelorder(&self) -> Vec<T> {
+        let mut result = Vec::new();
+        let mut queue = VecDeque::from([self.root]);
+        while let Some(id) = queue.pop_front() {
+            if id != NIL {
+                result.push(self.nodes[id].value.clone());
+                queue.push_back(self.nodes[id].left);
+                queue.push_back(self.nodes[id].right);
+            }
+        }
+        result
+    }
+
+    pub fn count(&self) -> u32 {
+        self.size_of(self.root) as u32
+    }
+
+    // k-th smallest value, counting from 0
+    pub fn select(&self, k: usize) -> Option<T> {
+        let mut k = k;
+        let mut id = self.root;
+        while id != NIL {
+            let left_size = self.size_of(self.nodes[id].left);
+            if k < left_size {
+                id = self.nodes[id].left;
+            } else if k > left_size {
+                k -= left_size + 1;
+                id = self.nodes[id].right;
+            } else {
+                return Some(self.nodes[id].value.clone());
+            }
+        }
+        None
+    }
+
+    // number of values strictly less than `value`
+    pub fn rank(&self, value: &T) -> usize {
+        self.count_below(value, false)
+    }
+
+    fn count_below(&self, value: &T, inclusive: bool) -> usize {
+        let mut count = 0;
+        let mut id = self.root;
+        while id != NIL {
+            let node = &self.nodes[id];
+            if node.value < *value || (inclusive && node.value == *value) {
+                count += self.size_of(node.left) + 1;
+                id = node.right;
+            } else {
+                id = node.left;
+            }
+        }
+        count
+    }
+
+    // lower median
+    pub fn median(&self) -> Option<T> {
+        let count = self.count() as usize;
+        if count == 0 {
+            return None;
+        }
+        self.select((count - 1) / 2)
+    }
+
+    // nearest-rank percentile, `p` in [0, 100]
+    pub fn percentile(&self, p: f64) -> Option<T> {
+        let count = self.count() as usize;
+        if count == 0 || !(0.0..=100.0).contains(&p) {
+            return None;
+        }
+        let rank = (p / 100.0 * count as f64).ceil() as usize;
+        self.select(rank.saturating_sub(1))
+    }
+
+    // checks ordering, cached heights and sizes, and balance of every node,
+    // reporting the first violation found
+    pub fn validate(&self) -> Result<(), InvariantViolation<T>> {
+        self.validate_rec(self.root, None, None).map(|_| ())
+    }
+
+    // returns the real height and size of the subtree
+    fn validate_rec(&self, id: NodeId, lower: Option<&T>, upper: Option<&T>) -> Result<(isize, usize), InvariantViolation<T>> {
+        if id == NIL {
+            return Ok((0, 0));
+        }
+        let node = &self.nodes[id];
+        let key = &node.value;
+        if lower.is_some_and(|lower| key <= lower) || upper.is_some_and(|upper| key >= upper) {
+            return Err(InvariantViolation::OutOfOrder { key: key.clone() });
+        }
+        let (left_height, left_size) = self.validate_rec(node.left, lower, Some(key))?;
+        let (right_height, right_size) = self.validate_rec(node.right, Some(key), upper)?;
+        let actual_height = 1 + std::cmp::max(left_height, right_height);
+        if node.height != actual_height {
+            return Err(InvariantViolation::StaleHeight { key: key.clone(), stored: node.height, actual: actual_height });
+        }
+        let balance = left_height - right_height;
+        if balance.abs() > 1 {
+            return Err(InvariantViolation::Unbalanced { key: key.clone(), balance });
+        }
+        let actual_size = 1 + left_size + right_size;
+        if node.size != actual_size {
+            return Err(InvariantViolation::StaleSize { key: key.clone(), stored: node.size, actual: actual_size });
+        }
+        Ok((actual_height, actual_size))
+    }
+}
+
+impl<T: Ord + Clone + Display> ArenaAVLTree<T> {
+    pub fn print_tree(&self) {
+        self.print_tree_rec(self.root, 0);
+    }
+
+    fn print_tree_rec(&self, id: NodeId, level: usize) {
+        if id != NIL {
+            // Print right subtree with increased indentation
+            self.print_tree_rec(self.nodes[id].right, level + 1);
+
+            // Print the current node value with the current level of indentation
+            for _ in 0..level {
+                print!("   ");
+            }
+            println!("{}", self.nodes[id].value);
+
+            // Print left subtree with increased indentation
+            self.print_tree_rec(self.nodes[id].left, level + 1);
+        }
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph AVLTree {\n");
+        self.to_dot_rec(self.root, &mut dot);
+        dot.push_str("}\n");
+        dot
+    }
+
+    fn to_dot_rec(&self, id: NodeId, output: &mut String) {
+        if id != NIL {
+            let node = &self.nodes[id];
+            if node.left != NIL {
+                output.push_str(&format!("    {} -> {} [label=\"L\"];\n", node.value, self.nodes[node.left].value));
+                self.to_dot_rec(node.left, output);
+            }
+            if node.right != NIL {
+                output.push_str(&format!("    {} -> {} [label=\"R\"];\n", node.value, self.nodes[node.right].value));
+                self.to_dot_rec(node.right, output);
+            }
+        }
+    }
+}
+
+impl<T: Ord + Clone> FromIterator<T> for ArenaAVLTree<T> {
+    // already sorted input skips the sort and goes straight to the O(n) build
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut values: Vec<T> = iter.into_iter().collect();
+        if !values.windows(2).all(|w| w[0] <= w[1]) {
+            values.sort();
+        }
+        Self::from_sorted_vec(values)
+    }
+}
+
+impl<T: Ord + Clone> Extend<T> for ArenaAVLTree<T> {
+    // a sorted run above the current maximum that is at least as large as the
+    // tree is cheaper to merge in with a rebuild than to insert one by one
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let values: Vec<T> = iter.into_iter().collect();
+        let sorted = values.windows(2).all(|w| w[0] <= w[1]);
+        let above_max = match (self.max(), values.first()) {
+            (Some(max), Some(first)) => max < *first,
+            _ => true,
+        };
+        if sorted && above_max && values.len() >= self.count() as usize {
+            let mut other = Self::from_sorted_vec(values);
+            self.append(&mut other);
+        } else {
+            for value in values {
+                self.insert(value);
+            }
+        }
+    }
+}
+
+/// In-order iterator over an `ArenaAVLTree`, yielding clones like
+/// `avltree::Iter`.
+pub struct Iter<'a, T: Ord + Clone> {
+    range: Range<'a, T>,
+}
+
+impl<'a, T: Ord + Clone> Iterator for Iter<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.range.next()
+    }
+}
+
+impl<'a, T: Ord + Clone> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.range.next_back()
+    }
+}
+
+/// In-order iterator over the values of an `ArenaAVLTree` that fall within a
+/// range, walking both ends with explicit stacks of node indices.
+pub struct Range<'a, T: Ord + Clone> {
+    tree: &'a ArenaAVLTree<T>,
+    front: Vec<NodeId>,
+    back: Vec<NodeId>,
+    // last nodes handed out from each end, used to detect when they meet
+    last_front: NodeId,
+    last_back: NodeId,
+    lower: Bound<T>,
+    upper: Bound<T>,
+    done: bool,
+}
+
+impl<'a, T: Ord + Clone> Range<'a, T> {
+    fn new(tree: &'a ArenaAVLTree<T>, lower: Bound<T>, upper: Bound<T>) -> Self {
+        let mut range = Range {
+            tree,
+            front: Vec::new(),
+            back: Vec::new(),
+            last_front: NIL,
+            last_back: NIL,
+            lower,
+            upper,
+            done: false,
+        };
+
+        // seed the front with the path to the first value above the lower bound
+        let mut id = tree.root;
+        while id != NIL {
+            if range.above_lower(&tree.nodes[id].value) {
+                range.front.push(id);
+                id = tree.nodes[id].left;
+            } else {
+                id = tree.nodes[id].right;
+            }
+        }
+
+        // and the back with the path to the last value below the upper bound
+        let mut id = tree.root;
+        while id != NIL {
+            if range.below_upper(&tree.nodes[id].value) {
+                range.back.push(id);
+                id = tree.nodes[id].right;
+            } else {
+                id = tree.nodes[id].left;
+            }
+        }
+        range
+    }
+
+    fn above_lower(&self, value: &T) -> bool {
+        match self.lower {
+            Bound::Included(ref lower) => value >= lower,
+            Bound::Excluded(ref lower) => value > lower,
+            Bound::Unbounded => true,
+        }
+    }
+
+    fn below_upper(&self, value: &T) -> bool {
+        match self.upper {
+            Bound::Included(ref upper) => value <= upper,
+            Bound::Excluded(ref upper) => value < upper,
+            Bound::Unbounded => true,
+        }
+    }
+}
+
+impl<'a, T: Ord + Clone> Iterator for Range<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.done {
+            return None;
+        }
+        let id = self.front.pop()?;
+        let node = &self.tree.nodes[id];
+        if id == self.last_back || !self.below_upper(&node.value) {
+            self.done = true;
+            return None;
+        }
+        let mut child = node.right;
+        while child != NIL {
+            self.front.push(child);
+            child = self.tree.nodes[child].left;
+        }
+        self.last_front = id;
+        Some(node.value.clone())
+    }
+}
+
+impl<'a, T: Ord + Clone> DoubleEndedIterator for Range<'a, T> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.done {
+            return None;
+        }
+        let id = self.back.pop()?;
+        let node = &self.tree.nodes[id];
+        if id == self.last_front || !self.above_lower(&node.value) {
+            self.done = true;
+            return None;
+        }
+        let mut child = node.left;
+        while child != NIL {
+            self.back.push(child);
+            child = self.tree.nodes[child].right;
+        }
+        self.last_back = id;
+        Some(node.value.clone())
+    }
+}
+
+impl<'a, T: Ord + Clone> IntoIterator for &'a ArenaAVLTree<T> {
+    type Item = T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+/// Owning iterator over the values of an `ArenaAVLTree`, in order.
+pub struct IntoIter<T> {
+    values: std::vec::IntoIter<T>,
+}
+
+impl<T: Ord + Clone> IntoIterator for ArenaAVLTree<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(mut self) -> IntoIter<T> {
+        IntoIter { values: self.take_values().into_iter() }
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.values.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.values.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.values.next_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T: Ord + Clone> OrderedSet<T> for ArenaAVLTree<T> {
+    fn insert(&mut self, value: T) -> bool {
+        ArenaAVLTree::insert(self, value)
+    }
+
+    fn remove(&mut self, value: &T) -> bool {
+        self.delete(value).is_ok()
+    }
+
+    fn contains(&self, value: &T) -> bool {
+        self.find(value) != NIL
+    }
+
+    fn len(&self) -> usize {
+        self.count() as usize
+    }
+
+    fn height(&self) -> usize {
+        ArenaAVLTree::height(self) as usize
+    }
+
+    fn count_leaves(&self) -> usize {
+        ArenaAVLTree::count_leaves(self)
+    }
+
+    fn min(&self) -> Option<T> {
+        ArenaAVLTree::min(self)
+    }
+
+    fn max(&self) -> Option<T> {
+        ArenaAVLTree::max(self)
+    }
+
+    fn inorder_traversal(&self) -> Vec<T> {
+        ArenaAVLTree::inorder_traversal(self)
+    }
+
+    fn preorder_traversal(&self) -> Vec<T> {
+        self.print_preorder()
+    }
+
+    fn levelorder_traversal(&self) -> Vec<T> {
+        self.print_levelorder()
+    }
+
+    fn validate(&self) -> Result<(), InvariantViolation<T>> {
+        ArenaAVLTree::validate(self)
+    }
+}
+
+impl<T: Ord + Clone + Display + Debug> PrintableSet<T> for ArenaAVLTree<T> {
+    fn print_tree(&self) {
+        ArenaAVLTree::print_tree(self)
+    }
+
+    fn to_dot(&self) -> String {
+        ArenaAVLTree::to_dot(self)
+    }
+}
@@ -0,0 +1,1230 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::fmt::{self, Debug, Display};
+use std::ops::{Bound, RangeBounds};
+
+use crate::arena::{Arena, NodeId, NIL};
+use crate::error::TreeError;
+use crate::invariant::InvariantViolation;
+use crate::ordered_set::{OrderedSet, PrintableSet};
+use crate::set_ops::{Difference, Intersection, SymmetricDifference, Union};
+
+#[derive(Clone, Debug, PartialEq, Copy)]
+enum NodeColor {
+    Red,
+    Black,
+}
+
+#[derive(Clone, Debug)]
+struct Node<T> {
+    color: NodeColor,
+    key: T,
+    parent: NodeId,
+    left: NodeId,
+    right: NodeId,
+    // number of nodes in the subtree rooted here
+    size: usize,
+}
+
+impl<T> Node<T> {
+    fn new(key: T) -> Self {
+        Node {
+            color: NodeColor::Red,
+            key,
+            parent: NIL,
+            left: NIL,
+            right: NIL,
+            size: 1,
+        }
+    }
+}
+
+/// `RBTree` with its nodes kept in a `Vec` arena and linked by `u32` index
+/// instead of `Rc<RefCell<...>>`. Parent links are plain indices, so there
+/// are no reference cycles and no runtime borrow checks.
+///
+/// It mirrors the core of `RBTree`: insertion and deletion, lookups by
+/// borrow, iterators and ranges, order statistics, nearest-key queries,
+/// split/append, set algebra, `validate` and `OrderedSet`. `search`, `min`
+/// and `max` return the key rather than a node handle. Keys always use
+/// their own `Ord`, and there are no cursors, `first`/`last`, `pop_*`,
+/// `retain` or `drain*` methods.
+#[derive(Clone, Debug)]
+pub struct ArenaRBTree<T: Ord> {
+    nodes: Arena<Node<T>>,
+    root: NodeId,
+    count: u32,
+}
+
+impl<T> Default for ArenaRBTree<T>
+where T: Ord
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> ArenaRBTree<T>
+where T: Ord
+{
+    pub fn new() -> Self {
+        ArenaRBTree {
+            nodes: Arena::new(),
+            root: NIL,
+            count: 0,
+        }
+    }
+
+    // builds a balanced tree in O(n) from keys in ascending order; repeated
+    // keys are kept once
+    pub fn from_sorted_vec(mut keys: Vec<T>) -> Self {
+        keys.dedup();
+        debug_assert!(keys.windows(2).all(|w| w[0] < w[1]), "from_sorted_vec requires sorted input");
+        let count = keys.len();
+        let mut tree = Self::new();
+        // all nodes are black except a partially filled bottom level, which is
+        // red so that every path sees the same number of black nodes
+        let max_depth = if count == 0 { 0 } else { count.ilog2() as usize };
+        tree.root = tree.build_balanced(count, 0, max_depth, &mut keys.into_iter());
+        tree.count = count as u32;
+        tree
+    }
+
+    pub fn from_sorted_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from_sorted_vec(iter.into_iter().collect())
+    }
+
+    fn build_balanced<I: Iterator<Item = T>>(&mut self, count: usize, depth: usize, max_depth: usize, keys: &mut I) -> NodeId {
+        if count == 0 {
+            return NIL;
+        }
+        let left_count = (count - 1) / 2;
+        let left = self.build_balanced(left_count, depth + 1, max_depth, keys);
+        let id = self.nodes.alloc(Node::new(keys.next().expect("fewer keys than count")));
+        let right = self.build_balanced(count - 1 - left_count, depth + 1, max_depth, keys);
+        for child in [left, right] {
+            if child != NIL {
+                self.nodes[child].parent = id;
+            }
+        }
+        let node = &mut self.nodes[id];
+        node.left = left;
+        node.right = right;
+        node.size = count;
+        node.color = if depth == max_depth && depth > 0 {
+            NodeColor::Red
+        } else {
+            NodeColor::Black
+        };
+        id
+    }
+
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    // check if tree is empty
+    pub fn is_empty(&self) -> bool {
+        self.root == NIL
+    }
+
+    // insert a node to the red-black tree, returning false if the key was
+    // already present
+    pub fn insert(&mut self, key: T) -> bool {
+        if self.find(&key) != NIL {
+            return false;
+        }
+        let mut parent = NIL;
+        let mut go_left = false;
+        let mut id = self.root;
+        while id != NIL {
+            parent = id;
+            self.nodes[id].size += 1;
+            go_left = key < self.nodes[id].key;
+            id = if go_left { self.nodes[id].left } else { self.nodes[id].right };
+        }
+        let added = self.nodes.alloc(Node::new(key));
+        self.nodes[added].parent = parent;
+        if parent == NIL {
+            self.root = added;
+        } else if go_left {
+            self.nodes[parent].left = added;
+        } else {
+            self.nodes[parent].right = added;
+        }
+        self.count += 1;
+        self.insert_fix(added);
+        true
+    }
+
+    fn insert_fix(&mut self, mut node: NodeId) {
+        while self.color_of(self.nodes[node].parent) == NodeColor::Red {
+            // a red parent is never the root, so the grandparent exists
+            let parent = self.nodes[node].parent;
+            let grandparent = self.nodes[parent].parent;
+            if parent == self.nodes[grandparent].left {
+                let uncle = self.nodes[grandparent].right;
+                if self.color_of(uncle) == NodeColor::Red {
+                    self.nodes[parent].color = NodeColor::Black;
+                    self.nodes[uncle].color = NodeColor::Black;
+                    self.nodes[grandparent].color = NodeColor::Red;
+                    node = grandparent;
+                } else {
+                    if node == self.nodes[parent].right {
+                        node = parent;
+                        self.rotate_left(node);
+                    }
+                    let parent = self.nodes[node].parent;
+                    let grandparent = self.nodes[parent].parent;
+                    self.nodes[parent].color = NodeColor::Black;
+                    self.nodes[grandparent].color = NodeColor::Red;
+                    self.rotate_right(grandparent);
+                }
+            } else {
+                let uncle = self.nodes[grandparent].left;
+                if self.color_of(uncle) == NodeColor::Red {
+                    self.nodes[parent].color = NodeColor::Black;
+                    self.nodes[uncle].color = NodeColor::Black;
+                    self.nodes[grandparent].color = NodeColor::Red;
+                    node = grandparent;
+                } else {
+                    if node == self.nodes[parent].left {
+                        node = parent;
+                        self.rotate_right(node);
+                    }
+                    let parent = self.nodes[node].parent;
+                    let grandparent = self.nodes[parent].parent;
+                    self.nodes[parent].color = NodeColor::Black;
+                    self.nodes[grandparent].color = NodeColor::Red;
+                    self.rotate_left(grandparent);
+                }
+            }
+        }
+        let root = self.root;
+        self.nodes[root].color = NodeColor::Black;
+    }
+
+    // nil leaves count as black
+    fn color_of(&self, id: NodeId) -> NodeColor {
+        if id == NIL { NodeColor::Black } else { self.nodes[id].color }
+    }
+
+    fn size_of(&self, id: NodeId) -> usize {
+        if id == NIL { 0 } else { self.nodes[id].size }
+    }
+
+    // recomputes the size of `id` from its children
+    fn update_size(&mut self, id: NodeId) {
+        let size = 1 + self.size_of(self.nodes[id].left) + self.size_of(self.nodes[id].right);
+        self.nodes[id].size = size;
+    }
+
+    // points whatever referred to `old` as a child (or the root) at `new`
+    fn replace_child(&mut self, parent: NodeId, old: NodeId, new: NodeId) {
+        if parent == NIL {
+            self.root = new;
+        } else if self.nodes[parent].left == old {
+            self.nodes[parent].left = new;
+        } else {
+            self.nodes[parent].right = new;
+        }
+    }
+
+    fn rotate_left(&mut self, x: NodeId) {
+        let y = self.nodes[x].right;
+        let y_left = self.nodes[y].left;
+        self.nodes[x].right = y_left;
+        if y_left != NIL {
+            self.nodes[y_left].parent = x;
+        }
+        let x_parent = self.nodes[x].parent;
+        self.nodes[y].parent = x_parent;
+        self.replace_child(x_parent, x, y);
+        self.nodes[y].left = x;
+        self.nodes[x].parent = y;
+        self.update_size(x);
+        self.update_size(y);
+    }
+
+    fn rotate_right(&mut self, x: NodeId) {
+        let y = self.nodes[x].left;
+        let y_right = self.nodes[y].right;
+        self.nodes[x].left = y_right;
+        if y_right != NIL {
+            self.nodes[y_right].parent = x;
+        }
+        let x_parent = self.nodes[x].parent;
+        self.nodes[y].parent = x_parent;
+        self.replace_child(x_parent, x, y);
+        self.nodes[y].right = x;
+        self.nodes[x].parent = y;
+        self.update_size(x);
+        self.update_size(y);
+    }
+
+    fn find<Q>(&self, key: &Q) -> NodeId
+    where T: Borrow<Q>, Q: Ord+?Sized
+    {
+        let mut id = self.root;
+        while id != NIL {
+            match key.cmp(self.nodes[id].key.borrow()) {
+                Ordering::Less => id = self.nodes[id].left,
+                Ordering::Greater => id = self.nodes[id].right,
+                Ordering::Equal => break,
+            }
+        }
+        id
+    }
+
+    pub fn search_element<Q>(&self, key: &Q) -> bool
+    where T: Borrow<Q>, Q: Ord+?Sized
+    {
+        self.find(key) != NIL
+    }
+
+    // delete a node from the red-black tree, returning its key
+    pub fn delete<Q>(&mut self, key: &Q) -> Result<T, TreeError>
+    where T: Borrow<Q>, Q: Ord+?Sized
+    {
+        self.remove_key(key).ok_or(TreeError::KeyNotFound)
+    }
+
+    // returns the removed key, if it was found
+    fn remove_key<Q>(&mut self, key: &Q) -> Option<T>
+    where T: Borrow<Q>, Q: Ord+?Sized
+    {
+        let u = self.find(key);
+        if u == NIL {
+            return None;
+        }
+        let (v, w) = (self.nodes[u].left, self.nodes[u].right);
+
+        let mut u_original_color = self.nodes[u].color;
+        let x: NodeId;
+        // x may be NIL, so its parent has to be tracked separately
+        let x_parent: NodeId;
+
+        if v == NIL {
+            x = w;
+            x_parent = self.nodes[u].parent;
+            self.transplant(u, w);
+        } else if w == NIL {
+            x = v;
+            x_parent = self.nodes[u].parent;
+            self.transplant(u, v);
+        } else {
+            let mut y = w;
+            while self.nodes[y].left != NIL {
+                y = self.nodes[y].left;
+            }
+            u_original_color = self.nodes[y].color;
+            x = self.nodes[y].right;
+            if self.nodes[y].parent == u {
+                x_parent = y;
+            } else {
+                x_parent = self.nodes[y].parent;
+                self.transplant(y, x);
+                self.nodes[y].right = w;
+                self.nodes[w].parent = y;
+            }
+            self.transplant(u, y);
+            self.nodes[y].left = v;
+            self.nodes[v].parent = y;
+            self.nodes[y].color = self.nodes[u].color;
+        }
+
+        // every subtree size changes on the path from the removed position up
+        let mut ancestor = x_parent;
+        while ancestor != NIL {
+            self.update_size(ancestor);
+            ancestor = self.nodes[ancestor].parent;
+        }
+
+        if u_original_color == NodeColor::Black {
+            self.delete_fix(x, x_parent);
+        }
+        self.count -= 1;
+        Some(self.nodes.release(u).key)
+    }
+
+    fn delete_fix(&mut self, mut x: NodeId, mut parent: NodeId) {
+        while x != self.root && self.color_of(x) == NodeColor::Black {
+            if x == self.nodes[parent].left {
+                let mut s = self.nodes[parent].right;
+                if self.color_of(s) == NodeColor::Red {
+                    self.nodes[s].color = NodeColor::Black;
+                    self.nodes[parent].color = NodeColor::Red;
+                    self.rotate_left(parent);
+                    s = self.nodes[parent].right;
+                }
+                let (s_left, s_right) = (self.nodes[s].left, self.nodes[s].right);
+                if self.color_of(s_left) == NodeColor::Black && self.color_of(s_right) == NodeColor::Black {
+                    self.nodes[s].color = NodeColor::Red;
+                    x = parent;
+                    parent = self.nodes[x].parent;
+                } else {
+                    if self.color_of(s_right) == NodeColor::Black {
+                        self.nodes[s_left].color = NodeColor::Black;
+                        self.nodes[s].color = NodeColor::Red;
+                        self.rotate_right(s);
+                        s = self.nodes[parent].right;
+                    }
+                    self.nodes[s].color = self.nodes[parent].color;
+                    self.nodes[parent].color = NodeColor::Black;
+                    let s_right = self.nodes[s].right;
+                    if s_right != NIL {
+                        self.nodes[s_right].color = NodeColor::Black;
+                    }
+                    self.rotate_left(parent);
+                    x = self.root;
+                }
+            } else {
+                let mut s = self.nodes[parent].left;
+                if self.color_of(s) == NodeColor::Red {
+                    self.nodes[s].color = NodeColor::Black;
+                    self.nodes[parent].color = NodeColor::Red;
+                    self.rotate_right(parent);
+                    s = self.nodes[parent].left;
+                }
+                let (s_left, s_right) = (self.nodes[s].left, self.nodes[s].right);
+                if self.color_of(s_left) == NodeColor::Black && self.color_of(s_right) == NodeColor::Black {
+                    self.nodes[s].color = NodeColor::Red;
+                    x = parent;
+                    parent = self.nodes[x].parent;
+                } else {
+                    if self.color_of(s_left) == NodeColor::Black {
+                        self.nodes[s_right].color = NodeColor::Black;
+                        self.nodes[s].color = NodeColor::Red;
+                        self.rotate_left(s);
+                        s = self.nodes[parent].left;
+                    }
+                    self.nodes[s].color = self.nodes[parent].color;
+                    self.nodes[parent].color = NodeColor::Black;
+                    let s_left = self.nodes[s].left;
+                    if s_left != NIL {
+                        self.nodes[s_left].color = NodeColor::Black;
+                    }
+                    self.rotate_right(parent);
+                    x = self.root;
+                }
+            }
+        }
+        if x != NIL {
+            self.nodes[x].color = NodeColor::Black;
+        }
+    }
+
+    fn transplant(&mut self, u: NodeId, v: NodeId) {
+        let u_parent = self.nodes[u].parent;
+        self.replace_child(u_parent, u, v);
+        if v != NIL {
+            self.nodes[v].parent = u_parent;
+        }
+    }
+
+    // ids of the subtree in order
+    fn inorder_ids(&self, id: NodeId, result: &mut Vec<NodeId>) {
+        if id != NIL {
+            self.inorder_ids(self.nodes[id].left, result);
+            result.push(id);
+            self.inorder_ids(self.nodes[id].right, result);
+        }
+    }
+
+    fn preorder_ids(&self) -> Vec<NodeId> {
+        let mut result = Vec::new();
+        let mut stack = vec![self.root];
+        while let Some(id) = stack.pop() {
+            if id != NIL {
+                result.push(id);
+                stack.push(self.nodes[id].right);
+                stack.push(self.nodes[id].left);
+            }
+        }
+        result
+    }
+
+    fn levelorder_ids(&self) -> Vec<NodeId> {
+        let mut result = Vec::new();
+        let mut queue = VecDeque::from([self.root]);
+        while let Some(id) = queue.pop_front() {
+            if id != NIL {
+                result.push(id);
+                queue.push_back(self.nodes[id].left);
+                queue.push_back(self.nodes[id].right);
+            }
+        }
+        result
+    }
+
+    fn min_id(&self) -> NodeId {
+        let mut id = self.root;
+        while id != NIL && self.nodes[id].left != NIL {
+            id = self.nodes[id].left;
+        }
+        id
+    }
+
+    fn max_id(&self) -> NodeId {
+        let mut id = self.root;
+        while id != NIL && self.nodes[id].right != NIL {
+            id = self.nodes[id].right;
+        }
+        id
+    }
+
+    // moves every key out in order, leaving the tree empty
+    fn take_keys(&mut self) -> Vec<T> {
+        let mut ids = Vec::with_capacity(self.count as usize);
+        self.inorder_ids(self.root, &mut ids);
+        let keys = ids.into_iter().map(|id| self.nodes.release(id).key).collect();
+        self.nodes.clear();
+        self.root = NIL;
+        self.count = 0;
+        keys
+    }
+
+    // splits the tree in two at `key`: self keeps the keys less than it and
+    // the returned tree gets the rest. Unlike `RBTree::split_off`, which
+    // cuts the tree in O(log n), this is O(n): the moved nodes need an arena
+    // of their own, so both sides are rebuilt.
+    pub fn split_off<Q>(&mut self, key: &Q) -> Self
+    where T: Borrow<Q>, Q: Ord+?Sized
+    {
+        let mut keys = self.take_keys();
+        let greater = keys.split_off(keys.partition_point(|k| k.borrow() < key));
+        *self = Self::from_sorted_vec(keys);
+        Self::from_sorted_vec(greater)
+    }
+
+    // moves every key of `other` into self, leaving `other` empty. Unlike
+    // `RBTree::append`, which joins trees with disjoint ranges in O(log n),
+    // this is always O(n + m): the nodes of `other` have to move into this
+    // arena anyway, so both trees are merged and rebuilt.
+    pub fn append(&mut self, other: &mut Self) {
+        if other.is_empty() {
+            return;
+        }
+        if self.is_empty() {
+            std::mem::swap(self, other);
+            return;
+        }
+        let keys = self.take_keys();
+        let other_keys = other.take_keys();
+        *self = Self::from_sorted_iter(Union::new(keys.into_iter(), other_keys.into_iter()));
+    }
+
+    // count the number of leaves in a tree
+    pub fn leaves(&self) -> u32 {
+        let mut count = 0;
+        let mut stack = vec![self.root];
+        while let Some(id) = stack.pop() {
+            if id == NIL {
+                continue;
+            }
+            let node = &self.nodes[id];
+            if node.left == NIL && node.right == NIL {
+                count += 1;
+            }
+            stack.push(node.left);
+            stack.push(node.right);
+        }
+        count
+    }
+
+    // return the height of a tree
+    pub fn height(&self) -> u32 {
+        self.height_rec(self.root)
+    }
+
+    fn height_rec(&self, id: NodeId) -> u32 {
+        if id == NIL {
+            return 0;
+        }
+        1 + std::cmp::max(self.height_rec(self.nodes[id].left), self.height_rec(self.nodes[id].right))
+    }
+
+    // number of keys within `range`, in O(log n) using subtree sizes
+    pub fn count_range<Q, R>(&self, range: R) -> usize
+    where T: Borrow<Q>, Q: Ord+?Sized, R: RangeBounds<Q>
+    {
+        let upper = match range.end_bound() {
+            Bound::Included(upper) => self.count_below(upper, true),
+            Bound::Excluded(upper) => self.count_below(upper, false),
+            Bound::Unbounded => self.count as usize,
+        };
+        let lower = match range.start_bound() {
+            Bound::Included(lower) => self.count_below(lower, false),
+            Bound::Excluded(lower) => self.count_below(lower, true),
+            Bound::Unbounded => 0,
+        };
+        upper.saturating_sub(lower)
+    }
+
+    // number of keys strictly less than `key`
+    pub fn rank<Q>(&self, key: &Q) -> usize
+    where T: Borrow<Q>, Q: Ord+?Sized
+    {
+        self.count_below(key, false)
+    }
+
+    fn count_below<Q>(&self, key: &Q, inclusive: bool) -> usize
+    where T: Borrow<Q>, Q: Ord+?Sized
+    {
+        let mut count = 0;
+        let mut id = self.root;
+        while id != NIL {
+            let node = &self.nodes[id];
+            let order = node.key.borrow().cmp(key);
+            if order == Ordering::Less || (inclusive && order == Ordering::Equal) {
+                count += self.size_of(node.left) + 1;
+                id = node.right;
+            } else {
+                id = node.left;
+            }
+        }
+        count
+    }
+}
+
+impl<T> ArenaRBTree<T>
+where T: Ord+Clone
+{
+    // returns the stored key equal to `key`, if any
+    pub fn search<Q>(&self, key: &Q) -> Option<T>
+    where T: Borrow<Q>, Q: Ord+?Sized
+    {
+        self.get(key)
+    }
+
+    // returns the stored key equal to `key`, if any
+    pub fn get<Q>(&self, key: &Q) -> Option<T>
+    where T: Borrow<Q>, Q: Ord+?Sized
+    {
+        let id = self.find(key);
+        (id != NIL).then(|| self.nodes[id].key.clone())
+    }
+
+    pub fn min(&self) -> Option<T> {
+        let id = self.min_id();
+        (id != NIL).then(|| self.nodes[id].key.clone())
+    }
+
+    pub fn max(&self) -> Option<T> {
+        let id = self.max_id();
+        (id != NIL).then(|| self.nodes[id].key.clone())
+    }
+
+    pub fn inorder_traversal(&self) -> Vec<T> {
+        self.iter().collect()
+    }
+
+    // lazy in-order iterator, see `Iter`
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { range: Range::new(self, Bound::Unbounded, Bound::Unbounded) }
+    }
+
+    // lazy in-order iterator over the keys within `range`
+    pub fn range<R: RangeBounds<T>>(&self, range: R) -> Range<'_, T> {
+        Range::new(self, range.start_bound().cloned(), range.end_bound().cloned())
+    }
+
+    // k-th smallest key, counting from 0
+    pub fn select(&self, k: usize) -> Option<T> {
+        let mut k = k;
+        let mut id = self.root;
+        while id != NIL {
+            let left_size = self.size_of(self.nodes[id].left);
+            if k < left_size {
+                id = self.nodes[id].left;
+            } else if k > left_size {
+                k -= left_size + 1;
+                id = self.nodes[id].right;
+            } else {
+                return Some(self.nodes[id].key.clone());
+            }
+        }
+        None
+    }
+
+    // lower median
+    pub fn median(&self) -> Option<T> {
+        let count = self.count as usize;
+        if count == 0 {
+            return None;
+        }
+        self.select((count - 1) / 2)
+    }
+
+    // nearest-rank percentile, `p` in [0, 100]
+    pub fn percentile(&self, p: f64) -> Option<T> {
+        let count = self.count as usize;
+        if count == 0 || !(0.0..=100.0).contains(&p) {
+            return None;
+        }
+        let rank = (p / 100.0 * count as f64).ceil() as usize;
+        self.select(rank.saturating_sub(1))
+    }
+
+    // checks ordering, coloring, black heights, parent pointers, cached sizes
+    // and the key count, reporting the first violation found
+    pub fn validate(&self) -> Result<(), InvariantViolation<T>> {
+        if self.root != NIL {
+            let root = &self.nodes[self.root];
+            if root.color == NodeColor::Red {
+                return Err(InvariantViolation::RedRoot { key: root.key.clone() });
+            }
+            if root.parent != NIL {
+                return Err(InvariantViolation::BrokenParent { key: root.key.clone() });
+            }
+        }
+        let (_, actual) = self.validate_rec(self.root, None, None)?;
+        if actual != self.count as usize {
+            return Err(InvariantViolation::CountMismatch { stored: self.count as usize, actual });
+        }
+        Ok(())
+    }
+
+    // returns the black height and size of the subtree
+    fn validate_rec(&self, id: NodeId, lower: Option<&T>, upper: Option<&T>) -> Result<(usize, usize), InvariantViolation<T>> {
+        if id == NIL {
+            return Ok((0, 0));
+        }
+        let node = &self.nodes[id];
+        let key = &node.key;
+        if lower.is_some_and(|lower| key <= lower) || upper.is_some_and(|upper| key >= upper) {
+            return Err(InvariantViolation::OutOfOrder { key: key.clone() });
+        }
+        for child in [node.left, node.right] {
+            if child == NIL {
+                continue;
+            }
+            let child = &self.nodes[child];
+            if child.parent != id {
+                return Err(InvariantViolation::BrokenParent { key: child.key.clone() });
+            }
+            if node.color == NodeColor::Red && child.color == NodeColor::Red {
+                return Err(InvariantViolation::RedRed { key: key.clone() });
+            }
+        }
+        let (left_bh, left_size) = self.validate_rec(node.left, lower, Some(key))?;
+        let (right_bh, right_size) = self.validate_rec(node.right, Some(key), upper)?;
+        if left_bh != right_bh {
+            return Err(InvariantViolation::BlackHeightMismatch { key: key.clone(), left: left_bh, right: right_bh });
+        }
+        let actual_size = 1 + left_size + right_size;
+        if node.size != actual_size {
+            return Err(InvariantViolation::StaleSize { key: key.clone(), stored: node.size, actual: actual_size });
+        }
+        let bh = if node.color == NodeColor::Black { left_bh + 1 } else { left_bh };
+        Ok((bh, actual_size))
+    }
+
+    // largest key less than or equal to `key`
+    pub fn floor<Q>(&self, key: &Q) -> Option<T>
+    where T: Borrow<Q>, Q: Ord+?Sized
+    {
+        self.closest(key, true, true)
+    }
+
+    // smallest key greater than or equal to `key`
+    pub fn ceiling<Q>(&self, key: &Q) -> Option<T>
+    where T: Borrow<Q>, Q: Ord+?Sized
+    {
+        self.closest(key, false, true)
+    }
+
+    // largest key strictly less than `key`
+    pub fn predecessor<Q>(&self, key: &Q) -> Option<T>
+    where T: Borrow<Q>, Q: Ord+?Sized
+    {
+        self.closest(key, true, false)
+    }
+
+    // smallest key strictly greater than `key`
+    pub fn successor<Q>(&self, key: &Q) -> Option<T>
+    where T: Borrow<Q>, Q: Ord+?Sized
+    {
+        self.closest(key, false, false)
+    }
+
+    // nearest key below (or above) `key` on a single root-to-leaf descent,
+    // `key` itself counting only when `inclusive`
+    fn closest<Q>(&self, key: &Q, below: bool, inclusive: bool) -> Option<T>
+    where T: Borrow<Q>, Q: Ord+?Sized
+    {
+        let mut best = NIL;
+        let mut id = self.root;
+        let wanted = if below { Ordering::Less } else { Ordering::Greater };
+        while id != NIL {
+            let order = self.nodes[id].key.borrow().cmp(key);
+            if order == wanted || (inclusive && order == Ordering::Equal) {
+                best = id;
+                if order == Ordering::Equal {
+                    break;
+                }
+            }
+            id = if order == Ordering::Greater || (below && order == Ordering::Equal) {
+                self.nodes[id].left
+            } else {
+                self.nodes[id].right
+            };
+        }
+        (best != NIL).then(|| self.nodes[best].key.clone())
+    }
+
+    // lazy iterator over the keys in self or `other`
+    pub fn union<'a>(&'a self, other: &'a Self) -> Union<Iter<'a, T>> {
+        Union::new(self.iter(), other.iter())
+    }
+
+    // lazy iterator over the keys in both self and `other`
+    pub fn intersection<'a>(&'a self, other: &'a Self) -> Intersection<Iter<'a, T>> {
+        Intersection::new(self.iter(), other.iter())
+    }
+
+    // lazy iterator over the keys in self but not in `other`
+    pub fn difference<'a>(&'a self, other: &'a Self) -> Difference<Iter<'a, T>> {
+        Difference::new(self.iter(), other.iter())
+    }
+
+    // lazy iterator over the keys in exactly one of self and `other`
+    pub fn symmetric_difference<'a>(&'a self, other: &'a Self) -> SymmetricDifference<Iter<'a, T>> {
+        SymmetricDifference::new(self.iter(), other.iter())
+    }
+
+    // new tree holding the union of self and `other`
+    pub fn union_tree(&self, other: &Self) -> Self {
+        Self::from_sorted_iter(self.union(other))
+    }
+
+    // new tree holding the intersection of self and `other`
+    pub fn intersection_tree(&self, other: &Self) -> Self {
+        Self::from_sorted_iter(self.intersection(other))
+    }
+
+    // new tree holding the keys of self that are not in `other`
+    pub fn difference_tree(&self, other: &Self) -> Self {
+        Self::from_sorted_iter(self.difference(other))
+    }
+
+    // new tree holding the symmetric difference of self and `other`
+    pub fn symmetric_difference_tree(&self, other: &Self) -> Self {
+        Self::from_sorted_iter(self.symmetric_difference(other))
+    }
+
+    // true if every key of self is also in `other`
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.count <= other.count && self.difference(other).next().is_none()
+    }
+
+    // true if every key of `other` is also in self
+    pub fn is_superset(&self, other: &Self) -> bool {
+        other.is_subset(self)
+    }
+
+    // true if self and `other` have no key in common
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.intersection(other).next().is_none()
+    }
+
+    pub fn preorder_traversal(&self) -> Vec<T> {
+        self.keys_of(self.preorder_ids())
+    }
+
+    pub fn levelorder_traversal(&self) -> Vec<T> {
+        self.keys_of(self.levelorder_ids())
+    }
+
+    fn keys_of(&self, ids: Vec<NodeId>) -> Vec<T> {
+        ids.into_iter().map(|id| self.nodes[id].key.clone()).collect()
+    }
+}
+
+impl<T> ArenaRBTree<T>
+where T: Ord+Display
+{
+    // print in-order traversal of tree
+    pub fn print_inorder(&self) {
+        let mut ids = Vec::with_capacity(self.count as usize);
+        self.inorder_ids(self.root, &mut ids);
+        self.print_keys(ids);
+    }
+
+    pub fn print_preorder(&self) {
+        self.print_keys(self.preorder_ids());
+    }
+
+    pub fn print_levelorder(&self) {
+        self.print_keys(self.levelorder_ids());
+    }
+
+    fn print_keys(&self, ids: Vec<NodeId>) {
+        if ids.is_empty() {
+            println!("None");
+            return;
+        }
+        for id in ids {
+            print!(" {} ", self.nodes[id].key);
+        }
+        println!("\n");
+    }
+
+    pub fn print_tree(&self) {
+        self.print_node(self.root, "Root", "");
+    }
+
+    fn print_node(&self, id: NodeId, node_type: &str, prefix: &str) {
+        if id != NIL {
+            let node = &self.nodes[id];
+            let color = match node.color {
+                NodeColor::Black => "black",
+                NodeColor::Red => "red",
+            };
+
+            let new_prefix = match node_type {
+                "Root" => "── ",
+                "L" => "├── ",
+                _ => "└── ",
+            };
+
+            println!("{}{}[{}] {}: {}", prefix, new_prefix, color, node_type, node.key);
+
+            let child_prefix = if node_type == "L" {
+                format!("{}│   ", prefix)
+            } else {
+                format!("{}    ", prefix)
+            };
+
+            self.print_node(node.left, "L", &child_prefix);
+            self.print_node(node.right, "R", &child_prefix);
+        }
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph RBTree {\n");
+        let mut queue = VecDeque::from([self.root]);
+        while let Some(id) = queue.pop_front() {
+            if id == NIL {
+                continue;
+            }
+            let node = &self.nodes[id];
+            let color = match node.color {
+                NodeColor::Black => "black",
+                NodeColor::Red => "red",
+            };
+            dot.push_str(&format!("    {} [color={}];\n", node.key, color));
+            if node.left != NIL {
+                dot.push_str(&format!("    {} -> {} [label=\"L\"];\n", node.key, self.nodes[node.left].key));
+            }
+            if node.right != NIL {
+                dot.push_str(&format!("    {} -> {} [label=\"R\"];\n", node.key, self.nodes[node.right].key));
+            }
+            queue.push_back(node.left);
+            queue.push_back(node.right);
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+impl<T> fmt::Display for ArenaRBTree<T>
+where T: Debug+Ord+Clone
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ArenaRBTree")
+         .field("keys", &self.inorder_traversal())
+         .field("length", &self.count)
+         .finish()
+    }
+}
+
+impl<T> FromIterator<T> for ArenaRBTree<T>
+where T: Ord
+{
+    // already sorted input skips the sort and goes straight to the O(n) build
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut keys: Vec<T> = iter.into_iter().collect();
+        if !keys.windows(2).all(|w| w[0] <= w[1]) {
+            keys.sort();
+        }
+        Self::from_sorted_vec(keys)
+    }
+}
+
+impl<T> Extend<T> for ArenaRBTree<T>
+where T: Ord
+{
+    // a sorted run above the current maximum that is at least as large as the
+    // tree is cheaper to merge in with a rebuild than to insert one by one
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let keys: Vec<T> = iter.into_iter().collect();
+        let sorted = keys.windows(2).all(|w| w[0] <= w[1]);
+        let max = self.max_id();
+        let above_max = match keys.first() {
+            Some(first) if max != NIL => self.nodes[max].key < *first,
+            _ => true,
+        };
+        if sorted && above_max && keys.len() >= self.count as usize {
+            let mut other = Self::from_sorted_vec(keys);
+            self.append(&mut other);
+        } else {
+            for key in keys {
+                self.insert(key);
+            }
+        }
+    }
+}
+
+/// In-order iterator over an `ArenaRBTree`, yielding copies of the keys.
+pub struct Iter<'a, T: Ord> {
+    range: Range<'a, T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T>
+where T: Ord+Clone
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.range.next()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T>
+where T: Ord+Clone
+{
+    fn next_back(&mut self) -> Option<T> {
+        self.range.next_back()
+    }
+}
+
+/// In-order iterator over the keys of an `ArenaRBTree` that fall within a
+/// range, walking both ends with explicit stacks of node indices.
+pub struct Range<'a, T: Ord> {
+    tree: &'a ArenaRBTree<T>,
+    front: Vec<NodeId>,
+    back: Vec<NodeId>,
+    // last nodes handed out from each end, used to detect when they meet
+    last_front: NodeId,
+    last_back: NodeId,
+    lower: Bound<T>,
+    upper: Bound<T>,
+    done: bool,
+}
+
+impl<'a, T> Range<'a, T>
+where T: Ord
+{
+    fn new(tree: &'a ArenaRBTree<T>, lower: Bound<T>, upper: Bound<T>) -> Self {
+        let mut range = Range {
+            tree,
+            front: Vec::new(),
+            back: Vec::new(),
+            last_front: NIL,
+            last_back: NIL,
+            lower,
+            upper,
+            done: false,
+        };
+
+        // seed the front with the path to the first key above the lower bound
+        let mut id = tree.root;
+        while id != NIL {
+            if range.above_lower(&tree.nodes[id].key) {
+                range.front.push(id);
+                id = tree.nodes[id].left;
+            } else {
+                id = tree.nodes[id].right;
+            }
+        }
+
+        // and the back with the path to the last key below the upper bound
+        let mut id = tree.root;
+        while id != NIL {
+            if range.below_upper(&tree.nodes[id].key) {
+                range.back.push(id);
+                id = tree.nodes[id].right;
+            } else {
+                id = tree.nodes[id].left;
+            }
+        }
+        range
+    }
+
+    fn above_lower(&self, key: &T) -> bool {
+        match self.lower {
+            Bound::Included(ref lower) => key >= lower,
+            Bound::Excluded(ref lower) => key > lower,
+            Bound::Unbounded => true,
+        }
+    }
+
+    fn below_upper(&self, key: &T) -> bool {
+        match self.upper {
+            Bound::Included(ref upper) => key <= upper,
+            Bound::Excluded(ref upper) => key < upper,
+            Bound::Unbounded => true,
+        }
+    }
+}
+
+impl<'a, T> Iterator for Range<'a, T>
+where T: Ord+Clone
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.done {
+            return None;
+        }
+        let id = self.front.pop()?;
+        let node = &self.tree.nodes[id];
+        if id == self.last_back || !self.below_upper(&node.key) {
+            self.done = true;
+            return None;
+        }
+        let mut child = node.right;
+        while child != NIL {
+            self.front.push(child);
+            child = self.tree.nodes[child].left;
+        }
+        self.last_front = id;
+        Some(node.key.clone())
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Range<'a, T>
+where T: Ord+Clone
+{
+    fn next_back(&mut self) -> Option<T> {
+        if self.done {
+            return None;
+        }
+        let id = self.back.pop()?;
+        let node = &self.tree.nodes[id];
+        if id == self.last_front || !self.above_lower(&node.key) {
+            self.done = true;
+            return None;
+        }
+        let mut child = node.left;
+        while child != NIL {
+            self.back.push(child);
+            child = self.tree.nodes[child].right;
+        }
+        self.last_back = id;
+        Some(node.key.clone())
+    }
+}
+
+impl<'a, T> IntoIterator for &'a ArenaRBTree<T>
+where T: Ord+Clone
+{
+    type Item = T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+/// Owning iterator over the keys of an `ArenaRBTree`, in order.
+pub struct IntoIter<T> {
+    keys: std::vec::IntoIter<T>,
+}
+
+impl<T> IntoIterator for ArenaRBTree<T>
+where T: Ord
+{
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(mut self) -> IntoIter<T> {
+        IntoIter { keys: self.take_keys().into_iter() }
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.keys.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.keys.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.keys.next_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> OrderedSet<T> for ArenaRBTree<T>
+where T: Ord+Clone
+{
+    fn insert(&mut self, value: T) -> bool {
+        ArenaRBTree::insert(self, value)
+    }
+
+    fn remove(&mut self, value: &T) -> bool {
+        self.remove_key(value).is_some()
+    }
+
+    fn contains(&self, value: &T) -> bool {
+        self.find(value) != NIL
+    }
+
+    fn len(&self) -> usize {
+        self.count as usize
+    }
+
+    fn height(&self) -> usize {
+        ArenaRBTree::height(self) as usize
+    }
+
+    fn count_leaves(&self) -> usize {
+        self.leaves() as usize
+    }
+
+    fn min(&self) -> Option<T> {
+        ArenaRBTree::min(self)
+    }
+
+    fn max(&self) -> Option<T> {
+        ArenaRBTree::max(self)
+    }
+
+    fn inorder_traversal(&self) -> Vec<T> {
+        ArenaRBTree::inorder_traversal(self)
+    }
+
+    fn preorder_traversal(&self) -> Vec<T> {
+        ArenaRBTree::preorder_traversal(self)
+    }
+
+    fn levelorder_traversal(&self) -> Vec<T> {
+        ArenaRBTree::levelorder_traversal(self)
+    }
+
+    fn validate(&self) -> Result<(), InvariantViolation<T>> {
+        ArenaRBTree::validate(self)
+    }
+}
+
+impl<T> PrintableSet<T> for ArenaRBTree<T>
+where T: Ord+Display+Debug+Clone
+{
+    fn print_tree(&self) {
+        ArenaRBTree::print_tree(self)
+    }
+
+    fn to_dot(&self) -> String {
+        ArenaRBTree::to_dot(self)
+    }
+}
@@ -0,0 +1,16 @@
+use crate::avltree::AVLTree;
+use crate::map;
+use crate::map_slots::MapKey;
+
+/// Ordered map on top of `AVLTree`: keys are kept balanced by the tree, each
+/// one pointing at its value in a side table.
+pub type AVLMap<K, V> = map::Map<K, V, AVLTree<MapKey<K>>>;
+
+/// A view into a single key of an `AVLMap`, from `AVLMap::entry`.
+pub type Entry<'a, K, V> = map::Entry<'a, K, V, AVLTree<MapKey<K>>>;
+
+/// An entry whose key is in the map.
+pub type OccupiedEntry<'a, K, V> = map::OccupiedEntry<'a, K, V, AVLTree<MapKey<K>>>;
+
+/// An entry whose key is missing.
+pub type VacantEntry<'a, K, V> = map::VacantEntry<'a, K, V, AVLTree<MapKey<K>>>;
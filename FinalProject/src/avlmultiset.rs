@@ -0,0 +1,11 @@
+use crate::avltree::AVLTree;
+use crate::map_slots::MapKey;
+use crate::multiset;
+
+/// Ordered multiset on top of `AVLTree`: each distinct key is stored once in
+/// the tree, pointing at its multiplicity in a side table.
+pub type AVLMultiset<T> = multiset::Multiset<T, AVLTree<MapKey<T>>>;
+
+/// In-order iterator over an `AVLMultiset`, yielding each key once per
+/// occurrence.
+pub type Iter<'a, T> = multiset::Iter<'a, T, AVLTree<MapKey<T>>>;
@@ -0,0 +1,123 @@
+use std::ops::{Bound, RangeBounds};
+
+use crate::avltree::AVLTree;
+use crate::invariant::InvariantViolation;
+use crate::summary::{above_lower, below_upper, refresh_summary, Annotated, Summary};
+
+type Link<T, S> = Option<std::rc::Rc<std::cell::RefCell<crate::avltree::AVLNode<Annotated<T, S>>>>>;
+
+/// Ordered set on top of `AVLTree` whose nodes cache the `Summary` of their
+/// subtree, kept current through every rotation and rebalance, so that the
+/// summary of any key range comes out in O(log n).
+#[derive(Debug)]
+pub struct AVLSummaryTree<T: Ord + Clone, S: Summary<T>> {
+    tree: AVLTree<Annotated<T, S>>,
+}
+
+impl<T: Ord + Clone, S: Summary<T>> Default for AVLSummaryTree<T, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord + Clone, S: Summary<T>> AVLSummaryTree<T, S> {
+    pub fn new() -> Self {
+        AVLSummaryTree { tree: AVLTree::with_refresh(refresh_summary) }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tree.count() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    // returns false if the key was already present
+    pub fn insert(&mut self, key: T) -> bool {
+        self.tree.insert(Annotated::new(key))
+    }
+
+    // returns false if the key was not present
+    pub fn remove(&mut self, key: &T) -> bool {
+        self.tree.delete(key).is_ok()
+    }
+
+    pub fn contains(&self, key: &T) -> bool {
+        self.tree.search(key)
+    }
+
+    // keys in ascending order
+    pub fn keys(&self) -> Vec<T> {
+        self.tree.iter().map(|entry| entry.key).collect()
+    }
+
+    // summary of every key, read off the root
+    pub fn total(&self) -> S {
+        self.tree.root.as_ref().map_or_else(S::identity, |root| root.borrow().value.summary.clone())
+    }
+
+    // summary of the keys within `range`, in O(log n)
+    pub fn aggregate<R: RangeBounds<T>>(&self, range: R) -> S {
+        Self::aggregate_rec(&self.tree.root, range.start_bound(), range.end_bound())
+    }
+
+    // once a node falls inside the range, its left subtree is bounded only
+    // from below and its right one only from above, so apart from the two
+    // boundary paths every subtree is taken whole from its cached summary
+    fn aggregate_rec(node: &Link<T, S>, lower: Bound<&T>, upper: Bound<&T>) -> S {
+        let node = match node {
+            Some(node) => node.borrow(),
+            None => return S::identity(),
+        };
+        if let (Bound::Unbounded, Bound::Unbounded) = (lower, upper) {
+            return node.value.summary.clone();
+        }
+        let key = &node.value.key;
+        if !above_lower(key, lower) {
+            return Self::aggregate_rec(&node.right, lower, upper);
+        }
+        if !below_upper(key, upper) {
+            return Self::aggregate_rec(&node.left, lower, upper);
+        }
+        let left = Self::aggregate_rec(&node.left, lower, Bound::Unbounded);
+        let right = Self::aggregate_rec(&node.right, Bound::Unbounded, upper);
+        left.combine(&S::of(key)).combine(&right)
+    }
+
+    // checks the AVL invariants of the underlying tree, then the cached
+    // summary of every node
+    pub fn validate(&self) -> Result<(), InvariantViolation<T>>
+    where S: PartialEq
+    {
+        self.tree.validate().map_err(|violation| violation.map_key(|entry| entry.key))?;
+        Self::validate_summaries(&self.tree.root).map(|_| ())
+    }
+
+    // returns the real summary of the subtree
+    fn validate_summaries(node: &Link<T, S>) -> Result<S, InvariantViolation<T>>
+    where S: PartialEq
+    {
+        let node = match node {
+            Some(node) => node.borrow(),
+            None => return Ok(S::identity()),
+        };
+        let left = Self::validate_summaries(&node.left)?;
+        let right = Self::validate_summaries(&node.right)?;
+        let actual = left.combine(&S::of(&node.value.key)).combine(&right);
+        if node.value.summary != actual {
+            return Err(InvariantViolation::StaleSummary { key: node.value.key.clone() });
+        }
+        Ok(actual)
+    }
+}
+
+impl<T: Ord + Clone, S: Summary<T>> FromIterator<T> for AVLSummaryTree<T, S> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut tree = Self::new();
+        for key in iter {
+            tree.insert(key);
+        }
+        tree
+    }
+}
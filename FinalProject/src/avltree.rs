@@ -1,351 +1,1552 @@
-use std::rc::Rc;
-use std::cell::RefCell;
-use std::io;
-use std::fs::File;
-use std::io::prelude::*;
-
-type AVLTreePtr<T> = Option<Rc<RefCell<AVLNode<T>>>>;
-
-#[cfg(feature = "debug_print")]
-macro_rules! debug_println {
-    ($($args:tt)*)=>{
-        println!($($args)*);
-    };
-}
-
-#[cfg(not(feature = "debug_print"))]
-macro_rules! debug_println {
-    ($($args:tt)*)=>{};
-}
-
-
-#[derive(Debug, Clone)]
-pub struct AVLNode<T: Ord + Clone> {
-    pub value: T,
-    pub left: AVLTreePtr<T>,
-    pub right: AVLTreePtr<T>,
-    pub height: isize,
-}
-
-#[derive(Debug)]
-pub struct AVLTree<T: Ord + Clone> {
-    pub root: AVLTreePtr<T>,
-}
-
-impl<T: Ord + Clone> AVLNode<T> {
-    fn new(value: T) -> Rc<RefCell<Self>> {
-        Rc::new(RefCell::new(AVLNode {
-            value,
-            left: None,
-            right: None,
-            height: 1,
-        }))
-    }
-
-    fn balance_factor(&self) -> isize {
-        let lh = self.left.as_ref().map_or(0, |l| l.borrow().height);
-        let rh = self.right.as_ref().map_or(0, |r| r.borrow().height);
-        lh - rh
-    }
-
-    fn update_height(&mut self) {
-        let lh = self.left.as_ref().map_or(0, |l| l.borrow().height);
-        let rh = self.right.as_ref().map_or(0, |r| r.borrow().height);
-        self.height = 1 + std::cmp::max(lh, rh);
-    }
-}
-
-impl<T: Ord + Clone + std::fmt::Display> AVLTree<T> {
-
-    pub fn print_tree(&self) {
-        self.print_tree_rec(&self.root, 0);
-    }
-
-    fn print_tree_rec(&self, node: &AVLTreePtr<T>, level: usize) {
-        if let Some(curr) = node {
-            // Print right subtree with increased indentation
-            self.print_tree_rec(&curr.borrow().right, level + 1);
-
-            // Print the current node value with the current level of indentation
-            for _ in 0..level {
-                print!("   ");
-            }
-            println!("{}", curr.borrow().value);
-
-            // Print left subtree with increased indentation
-            self.print_tree_rec(&curr.borrow().left, level + 1);
-        }
-    }
-
-    pub fn new() -> Self {
-        AVLTree { root: None }
-    }
-
-    pub fn is_empty(&self) -> bool {
-        self.root.is_none()
-    }
-
-    pub fn insert(&mut self, value: T) {
-        if self.root.is_none() {
-            self.root = Some(AVLNode::new(value));
-        } else {
-            let taken_root = self.root.take();
-            self.root = Some(self.insert_rec(taken_root, value));
-        }
-    }
-    
-    fn insert_rec(&self, node: AVLTreePtr<T>, value: T) -> Rc<RefCell<AVLNode<T>>> {
-        let current_node = node.clone().unwrap_or_else(|| AVLNode::new(value.clone()));
-        {
-            let mut current_node_ref = current_node.borrow_mut();
-            if value < current_node_ref.value {
-                current_node_ref.left = Some(self.insert_rec(current_node_ref.left.clone(), value));
-            } else if value > current_node_ref.value {
-                current_node_ref.right = Some(self.insert_rec(current_node_ref.right.clone(), value));
-            } else {
-                return current_node.clone();
-            }
-            current_node_ref.update_height();
-        }
-        self.balance(current_node)
-    }
-
-    fn balance(&self, node: Rc<RefCell<AVLNode<T>>>) -> Rc<RefCell<AVLNode<T>>> {
-        let balance_factor = node.borrow().balance_factor();
-
-        // Left heavy
-        if balance_factor > 1 {
-            let left_child_balance = node.borrow().left.as_ref().unwrap().borrow().balance_factor();
-            if left_child_balance < 0 {
-                let left_child = node.borrow_mut().left.clone().unwrap();
-                node.borrow_mut().left = Some(self.rotate_left(left_child));
-            }
-            return self.rotate_right(node);
-        }
-
-        // Right heavy
-        if balance_factor < -1 {
-            let right_child_balance = node.borrow().right.as_ref().unwrap().borrow().balance_factor();
-            if right_child_balance > 0 {
-                let right_child = node.borrow_mut().right.clone().unwrap();
-                node.borrow_mut().right = Some(self.rotate_right(right_child));
-            }
-            return self.rotate_left(node);
-        }
-
-        node
-    }
-
-    fn rotate_left(&self, x: Rc<RefCell<AVLNode<T>>>) -> Rc<RefCell<AVLNode<T>>> {
-        debug_println!("Rotating left node with value: {}", x.borrow().value);
-
-        let y = x.borrow_mut().right.take().expect("rotate_left requires a right child");
-        let t2 = y.borrow_mut().left.take();
-    
-        // Perform rotation
-        y.borrow_mut().left = Some(x.clone());
-        x.borrow_mut().right = t2;
-    
-        // Update heights
-        x.borrow_mut().update_height();
-        y.borrow_mut().update_height();
-    
-        y
-    }
-    
-
-    fn rotate_right(&self, y: Rc<RefCell<AVLNode<T>>>) -> Rc<RefCell<AVLNode<T>>> {
-        debug_println!("Rotating right node with value: {}", y.borrow().value);
-
-        let x = y.borrow_mut().left.take().unwrap();
-        let t3 = x.borrow_mut().right.take();
-
-        // Perform rotation
-        x.borrow_mut().right = Some(y.clone());
-        y.borrow_mut().left = t3;
-
-        // Update heights
-        y.borrow_mut().update_height();
-        x.borrow_mut().update_height();
-
-        x
-    }
-    pub fn delete(&mut self, value: T) {
-        let taken_root = self.root.take();
-        self.root = self.delete_rec(taken_root, value);
-    }
-    fn delete_rec(&self, node: AVLTreePtr<T>, value: T) -> AVLTreePtr<T> {
-        if let Some(current_node) = node {
-            {
-                let mut node_borrow = current_node.borrow_mut();
-
-                if value < node_borrow.value {
-                    node_borrow.left = self.delete_rec(node_borrow.left.take(), value);
-                } else if value > node_borrow.value {
-                    node_borrow.right = self.delete_rec(node_borrow.right.take(), value);
-                } else {
-                    if node_borrow.left.is_some() && node_borrow.right.is_some() {
-                        // Find the inorder successor's value
-                        let temp = self.min_value_node(node_borrow.right.as_ref().unwrap().clone());
-                        let inorder_successor_value = temp.borrow().value.clone();
-                        // Now, delete the inorder successor.
-                        node_borrow.right = self.delete_rec(node_borrow.right.take(), inorder_successor_value.clone());
-                        // Assign the inorder successor value to the current node.
-                        node_borrow.value = inorder_successor_value;
-                    } else if node_borrow.left.is_some() {
-                        return Some(self.balance(node_borrow.left.take().unwrap()));
-                    } else if node_borrow.right.is_some() {
-                        return Some(self.balance(node_borrow.right.take().unwrap()));
-                    } else {
-                        return None;
-                    }
-                }
-                node_borrow.update_height();
-            }
-            Some(self.balance(current_node))
-        } else {
-            None
-        }
-    }
-        
-    fn min_value_node(&self, node: Rc<RefCell<AVLNode<T>>>) -> Rc<RefCell<AVLNode<T>>> {
-        let mut current = node;
-        while current.borrow().left.is_some() {
-            let next_node = current.borrow().left.clone();
-            if let Some(inner_node) = next_node {
-                current = inner_node;
-            }
-        }
-        current
-    }
-    pub fn count_leaves(&self) -> usize {
-        self.count_leaves_rec(&self.root)
-    }
-
-    fn count_leaves_rec(&self, node: &AVLTreePtr<T>) -> usize {
-        if let Some(curr) = node {
-            let l_count = self.count_leaves_rec(&curr.borrow().left);
-            let r_count = self.count_leaves_rec(&curr.borrow().right);
-            if curr.borrow().left.is_none() && curr.borrow().right.is_none() {
-                return 1;
-            }
-            l_count + r_count
-        } else {
-            0
-        }
-    }
-    pub fn inorder_traversal(&self) -> Vec<T> {
-        let mut result = Vec::new();
-        self.inorder_traversal_rec(&self.root, &mut result);
-        result
-    }
-
-    fn inorder_traversal_rec(&self, node: &AVLTreePtr<T>, result: &mut Vec<T>) {
-        if let Some(curr) = node {
-            self.inorder_traversal_rec(&curr.borrow().left, result);
-            result.push(curr.borrow().value.clone());
-            self.inorder_traversal_rec(&curr.borrow().right, result);
-        }
-    }
-
-    pub fn height(&self) -> isize {
-        self.root.as_ref().map_or(0, |r| r.borrow().height)
-    }
-    pub fn to_dot(&self) -> String {
-        let mut dot = String::from("digraph AVLTree {\n");
-        self.to_dot_rec(&self.root, &mut dot);
-        dot.push_str("}\n");
-        dot
-    }
-    
-    fn to_dot_rec(&self, node: &AVLTreePtr<T>, output: &mut String) {
-        if let Some(curr) = node {
-            let curr_val = format!("{}", curr.borrow().value);
-            if let Some(left) = &curr.borrow().left {
-                let left_val = format!("{}", left.borrow().value);
-                output.push_str(&format!("    {} -> {} [label=\"L\"];\n", curr_val, left_val));
-                self.to_dot_rec(&curr.borrow().left, output);
-            }
-            if let Some(right) = &curr.borrow().right {
-                let right_val = format!("{}", right.borrow().value);
-                output.push_str(&format!("    {} -> {} [label=\"R\"];\n", curr_val, right_val));
-                self.to_dot_rec(&curr.borrow().right, output);
-            }
-        }
-    }
-    pub fn search(&self, value: T) -> bool {
-        self.search_rec(&self.root, value)
-    }
-
-    fn search_rec(&self, node: &AVLTreePtr<T>, value: T) -> bool {
-        match node {
-            Some(current_node) => {
-                let current_value = &current_node.borrow().value;
-                if *current_value == value {
-                    true
-                } else if value < *current_value {
-                    self.search_rec(&current_node.borrow().left, value)
-                } else {
-                    self.search_rec(&current_node.borrow().right, value)
-                }
-            },
-            None => false,
-        }
-    }
-
-    pub fn print_preorder(&self) -> Vec<T> {
-        let mut result = Vec::new();
-        self.preorder_traversal_rec(&self.root, &mut result);
-        result
-    }
-    
-    fn preorder_traversal_rec(&self, node: &AVLTreePtr<T>, result: &mut Vec<T>) {
-        if let Some(curr) = node {
-            result.push(curr.borrow().value.clone()); // Visit the root node
-            self.preorder_traversal_rec(&curr.borrow().left, result); // Then visit the left subtree
-            self.preorder_traversal_rec(&curr.borrow().right, result); // Finally, visit the right subtree
-        }
-    }
-    
-
-    pub fn print_levelorder(&self) -> Vec<T> {
-        let mut result = Vec::new();
-        if let Some(root) = &self.root {
-            let mut queue = std::collections::VecDeque::new();
-            queue.push_back(root.clone());
-    
-            while !queue.is_empty() {
-                let current = queue.pop_front().unwrap();
-                let current_ref = current.borrow();
-                result.push(current_ref.value.clone());
-    
-                if let Some(left) = &current_ref.left {
-                    queue.push_back(left.clone());
-                }
-    
-                if let Some(right) = &current_ref.right {
-                    queue.push_back(right.clone());
-                }
-            }
-        }
-    
-        result
-    }
-    
-
-    pub fn count(&self) -> u32 {
-        self.count_rec(&self.root)
-    }
-
-    fn count_rec(&self, node: &AVLTreePtr<T>) -> u32 {
-        if let Some(current_node) = node {
-           
-            1 + self.count_rec(&current_node.borrow().left) + self.count_rec(&current_node.borrow().right)
-        } else {
-         
-            0
-        }
-    }
-
-
-}
+use std::rc::Rc;
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::fmt::{Debug, Display};
+use std::marker::PhantomData;
+use std::ops::{Bound, RangeBounds};
+
+use crate::comparator::{Comparator, Natural};
+use crate::error::TreeError;
+use crate::invariant::InvariantViolation;
+use crate::ordered_set::{OrderedSet, PrintableSet};
+use crate::set_ops::{Difference, Intersection, SymmetricDifference, Union};
+use crate::summary::Refresh;
+
+type AVLTreePtr<T> = Option<Rc<RefCell<AVLNode<T>>>>;
+
+#[cfg(feature = "debug_print")]
+macro_rules! debug_println {
+    ($($args:tt)*)=>{
+        println!($($args)*);
+    };
+}
+
+#[cfg(not(feature = "debug_print"))]
+macro_rules! debug_println {
+    ($($args:tt)*)=>{};
+}
+
+
+#[derive(Debug, Clone)]
+pub struct AVLNode<T: Clone> {
+    pub value: T,
+    pub left: AVLTreePtr<T>,
+    pub right: AVLTreePtr<T>,
+    pub height: isize,
+    // number of nodes in the subtree rooted here
+    pub size: usize,
+}
+
+// Outcome of a single descent looking for a value: the nodes passed on the
+// way down, each with whether the descent went left from it, ending either
+// at the node holding the value or where it would be linked.
+pub(crate) enum Probe<T: Clone> {
+    Found(ProbePath<T>, Rc<RefCell<AVLNode<T>>>),
+    Vacant(ProbePath<T>),
+}
+
+pub(crate) type ProbePath<T> = Vec<(Rc<RefCell<AVLNode<T>>>, bool)>;
+
+/// AVL tree ordered by the comparator `C`, which defaults to the values'
+/// own `Ord`; see `AVLTree::with_comparator` for anything else.
+pub struct AVLTree<T: Clone, C = Natural> {
+    pub root: AVLTreePtr<T>,
+    refresh: Option<Refresh<T>>,
+    comparator: C,
+}
+
+impl<T: Clone + Debug, C> Debug for AVLTree<T, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("AVLTree").field("root", &self.root).finish()
+    }
+}
+
+impl<T: Clone> AVLNode<T> {
+    fn new(value: T) -> Rc<RefCell<Self>> {
+        Rc::new(RefCell::new(AVLNode {
+            value,
+            left: None,
+            right: None,
+            height: 1,
+            size: 1,
+        }))
+    }
+
+    fn balance_factor(&self) -> isize {
+        let lh = self.left.as_ref().map_or(0, |l| l.borrow().height);
+        let rh = self.right.as_ref().map_or(0, |r| r.borrow().height);
+        lh - rh
+    }
+
+    // recomputes height and size from the children, along with the data the
+    // value caches about its subtree when the tree has a refresh hook
+    fn update(&mut self, refresh: Option<Refresh<T>>) {
+        let lh = self.left.as_ref().map_or(0, |l| l.borrow().height);
+        let rh = self.right.as_ref().map_or(0, |r| r.borrow().height);
+        self.height = 1 + std::cmp::max(lh, rh);
+        self.size = 1 + size(&self.left) + size(&self.right);
+        if let Some(refresh) = refresh {
+            let left = self.left.as_ref().map(|left| left.borrow());
+            let right = self.right.as_ref().map(|right| right.borrow());
+            refresh(&mut self.value, left.as_ref().map(|left| &left.value), right.as_ref().map(|right| &right.value));
+        }
+    }
+}
+
+fn size<T: Clone>(node: &AVLTreePtr<T>) -> usize {
+    node.as_ref().map_or(0, |n| n.borrow().size)
+}
+
+fn height<T: Clone>(node: &AVLTreePtr<T>) -> isize {
+    node.as_ref().map_or(0, |n| n.borrow().height)
+}
+
+impl<T: Clone, C: Comparator<T> + Default> Default for AVLTree<T, C> {
+    fn default() -> Self {
+        Self::with_comparator(C::default())
+    }
+}
+
+impl<T: Ord + Clone> AVLTree<T> {
+    pub fn new() -> Self {
+        Self::with_comparator(Natural)
+    }
+
+    // builds a perfectly balanced tree in O(n) from values in ascending
+    // order; repeated values are kept once
+    pub fn from_sorted_vec(values: Vec<T>) -> Self {
+        Self::from_sorted_vec_with(values, Natural)
+    }
+
+    pub fn from_sorted_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from_sorted_vec(iter.into_iter().collect())
+    }
+
+    // empty tree whose values cache subtree data kept current by `refresh`
+    pub(crate) fn with_refresh(refresh: Refresh<T>) -> Self {
+        let mut tree = Self::new();
+        tree.refresh = Some(refresh);
+        tree
+    }
+
+    // lazy iterator over the values in self or `other`
+    pub fn union<'a>(&'a self, other: &'a Self) -> Union<Iter<'a, T>> {
+        Union::new(self.iter(), other.iter())
+    }
+
+    // lazy iterator over the values in both self and `other`
+    pub fn intersection<'a>(&'a self, other: &'a Self) -> Intersection<Iter<'a, T>> {
+        Intersection::new(self.iter(), other.iter())
+    }
+
+    // lazy iterator over the values in self but not in `other`
+    pub fn difference<'a>(&'a self, other: &'a Self) -> Difference<Iter<'a, T>> {
+        Difference::new(self.iter(), other.iter())
+    }
+
+    // lazy iterator over the values in exactly one of self and `other`
+    pub fn symmetric_difference<'a>(&'a self, other: &'a Self) -> SymmetricDifference<Iter<'a, T>> {
+        SymmetricDifference::new(self.iter(), other.iter())
+    }
+
+    // new tree holding the union of self and `other`
+    pub fn union_tree(&self, other: &Self) -> Self {
+        Self::from_sorted_iter(self.union(other))
+    }
+
+    // new tree holding the intersection of self and `other`
+    pub fn intersection_tree(&self, other: &Self) -> Self {
+        Self::from_sorted_iter(self.intersection(other))
+    }
+
+    // new tree holding the values of self that are not in `other`
+    pub fn difference_tree(&self, other: &Self) -> Self {
+        Self::from_sorted_iter(self.difference(other))
+    }
+
+    // new tree holding the symmetric difference of self and `other`
+    pub fn symmetric_difference_tree(&self, other: &Self) -> Self {
+        Self::from_sorted_iter(self.symmetric_difference(other))
+    }
+
+    // true if every value of self is also in `other`
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.count() <= other.count() && self.difference(other).next().is_none()
+    }
+
+    // true if every value of `other` is also in self
+    pub fn is_superset(&self, other: &Self) -> bool {
+        other.is_subset(self)
+    }
+
+    // true if self and `other` have no value in common
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.intersection(other).next().is_none()
+    }
+}
+
+impl<T: Clone, C: Comparator<T>> AVLTree<T, C> {
+    // empty tree ordered by `comparator`, e.g. `|a: &T, b: &T| b.cmp(a)` for
+    // descending order
+    pub fn with_comparator(comparator: C) -> Self {
+        AVLTree { root: None, refresh: None, comparator }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    // builds a perfectly balanced tree in O(n) from values already in
+    // `comparator` order; repeated values are kept once
+    pub fn from_sorted_vec_with(values: Vec<T>, comparator: C) -> Self {
+        Self::build_sorted(values, comparator, None)
+    }
+
+    fn build_sorted(mut values: Vec<T>, comparator: C, refresh: Option<Refresh<T>>) -> Self {
+        values.dedup_by(|a, b| comparator.compare(a, b) == Ordering::Equal);
+        debug_assert!(values.windows(2).all(|w| comparator.compare(&w[0], &w[1]) == Ordering::Less), "from_sorted_vec requires sorted input");
+        let count = values.len();
+        AVLTree { root: Self::build_balanced(count, &mut values.into_iter(), refresh), refresh, comparator }
+    }
+
+    // builds a subtree of `count` nodes taking values from `values` in order,
+    // so both sides differ in size by at most one
+    fn build_balanced<I: Iterator<Item = T>>(count: usize, values: &mut I, refresh: Option<Refresh<T>>) -> AVLTreePtr<T> {
+        if count == 0 {
+            return None;
+        }
+        let left_count = (count - 1) / 2;
+        let left = Self::build_balanced(left_count, values, refresh);
+        let node = AVLNode::new(values.next().expect("fewer values than count"));
+        let right = Self::build_balanced(count - 1 - left_count, values, refresh);
+        {
+            let mut node_ref = node.borrow_mut();
+            node_ref.left = left;
+            node_ref.right = right;
+            node_ref.update(refresh);
+        }
+        Some(node)
+    }
+
+    // returns false if the value was already present
+    pub fn insert(&mut self, value: T) -> bool {
+        match self.probe(&value) {
+            Probe::Found(..) => false,
+            Probe::Vacant(path) => {
+                self.insert_vacant(path, value);
+                true
+            }
+        }
+    }
+
+    // compares `value` against a stored value through its borrowed form
+    fn cmp_value<Q>(&self, value: &Q, stored: &T) -> Ordering
+    where T: std::borrow::Borrow<Q>, C: Comparator<Q>, Q: ?Sized
+    {
+        self.comparator.compare(value, <T as std::borrow::Borrow<Q>>::borrow(stored))
+    }
+
+    // looks `value` up in a single descent, recording the path down to where
+    // it would be linked if it is missing
+    pub(crate) fn probe<Q>(&self, value: &Q) -> Probe<T>
+    where T: std::borrow::Borrow<Q>, C: Comparator<Q>, Q: ?Sized
+    {
+        let mut path = Vec::new();
+        let mut current = self.root.clone();
+        while let Some(node) = current {
+            let order = self.cmp_value(value, &node.borrow().value);
+            current = match order {
+                Ordering::Less => node.borrow().left.clone(),
+                Ordering::Greater => node.borrow().right.clone(),
+                Ordering::Equal => return Probe::Found(path, node),
+            };
+            path.push((node, order == Ordering::Less));
+        }
+        Probe::Vacant(path)
+    }
+
+    // links a new leaf at the end of a `Probe::Vacant` path, then walks the
+    // path back up updating and rebalancing each ancestor
+    pub(crate) fn insert_vacant(&mut self, mut path: ProbePath<T>, value: T) {
+        let mut subtree = AVLNode::new(value);
+        subtree.borrow_mut().update(self.refresh);
+        while let Some((node, go_left)) = path.pop() {
+            {
+                let mut node_ref = node.borrow_mut();
+                if go_left {
+                    node_ref.left = Some(subtree);
+                } else {
+                    node_ref.right = Some(subtree);
+                }
+                node_ref.update(self.refresh);
+            }
+            subtree = self.balance(node);
+        }
+        self.root = Some(subtree);
+    }
+
+    // unlinks the node at the end of a `Probe::Found` path, then walks the
+    // path back up updating and rebalancing each ancestor, returning the
+    // value that was stored
+    pub(crate) fn remove_found(&mut self, mut path: ProbePath<T>, node: Rc<RefCell<AVLNode<T>>>) -> T {
+        let (mut subtree, removed) = self.detach(node);
+        while let Some((node, go_left)) = path.pop() {
+            {
+                let mut node_ref = node.borrow_mut();
+                if go_left {
+                    node_ref.left = subtree;
+                } else {
+                    node_ref.right = subtree;
+                }
+                node_ref.update(self.refresh);
+            }
+            subtree = Some(self.balance(node));
+        }
+        self.root = subtree;
+        take_value(removed)
+    }
+
+    // takes `node` out of its subtree, returning the rebalanced subtree that
+    // replaces it and the node left holding the removed value: `node` itself,
+    // or its inorder successor when it has two children, whose value it
+    // takes over instead
+    fn detach(&self, node: Rc<RefCell<AVLNode<T>>>) -> (AVLTreePtr<T>, Rc<RefCell<AVLNode<T>>>) {
+        let mut node_ref = node.borrow_mut();
+        if node_ref.left.is_some() && node_ref.right.is_some() {
+            let (rest, successor) = self.remove_min_rec(node_ref.right.take().unwrap());
+            std::mem::swap(&mut node_ref.value, &mut successor.borrow_mut().value);
+            node_ref.right = rest;
+            node_ref.update(self.refresh);
+            drop(node_ref);
+            (Some(self.balance(node)), successor)
+        } else {
+            let child = node_ref.left.take().or_else(|| node_ref.right.take());
+            drop(node_ref);
+            (child, node)
+        }
+    }
+
+    // extends `upward`, a path from `target` up to some node, with the nodes
+    // from `root` down to that node; from `root` down to `target` itself when
+    // `upward` is empty
+    fn track_down(&self, upward: &mut Vec<Rc<RefCell<AVLNode<T>>>>, root: Rc<RefCell<AVLNode<T>>>, target: &Rc<RefCell<AVLNode<T>>>) {
+        let mut down = Vec::new();
+        let mut current = root;
+        while !upward.last().is_some_and(|anchor| Rc::ptr_eq(anchor, &current)) {
+            down.push(current.clone());
+            if Rc::ptr_eq(&current, target) {
+                break;
+            }
+            let order = self.comparator.compare(&target.borrow().value, &current.borrow().value);
+            let next = if order == Ordering::Less {
+                current.borrow().left.clone()
+            } else {
+                current.borrow().right.clone()
+            };
+            current = next.expect("the target is below the root");
+        }
+        upward.extend(down.into_iter().rev());
+    }
+
+    fn balance(&self, node: Rc<RefCell<AVLNode<T>>>) -> Rc<RefCell<AVLNode<T>>> {
+        let balance_factor = node.borrow().balance_factor();
+
+        // Left heavy
+        if balance_factor > 1 {
+            let left_child_balance = node.borrow().left.as_ref().unwrap().borrow().balance_factor();
+            if left_child_balance < 0 {
+                let left_child = node.borrow_mut().left.clone().unwrap();
+                node.borrow_mut().left = Some(self.rotate_left(left_child));
+            }
+            return self.rotate_right(node);
+        }
+
+        // Right heavy
+        if balance_factor < -1 {
+            let right_child_balance = node.borrow().right.as_ref().unwrap().borrow().balance_factor();
+            if right_child_balance > 0 {
+                let right_child = node.borrow_mut().right.clone().unwrap();
+                node.borrow_mut().right = Some(self.rotate_right(right_child));
+            }
+            return self.rotate_left(node);
+        }
+
+        node
+    }
+
+    fn rotate_left(&self, x: Rc<RefCell<AVLNode<T>>>) -> Rc<RefCell<AVLNode<T>>> {
+        debug_println!("Rotating left");
+
+        let y = x.borrow_mut().right.take().expect("rotate_left requires a right child");
+        let t2 = y.borrow_mut().left.take();
+    
+        // Perform rotation
+        y.borrow_mut().left = Some(x.clone());
+        x.borrow_mut().right = t2;
+    
+        // Update heights
+        x.borrow_mut().update(self.refresh);
+        y.borrow_mut().update(self.refresh);
+    
+        y
+    }
+    
+
+    fn rotate_right(&self, y: Rc<RefCell<AVLNode<T>>>) -> Rc<RefCell<AVLNode<T>>> {
+        debug_println!("Rotating right");
+
+        let x = y.borrow_mut().left.take().unwrap();
+        let t3 = x.borrow_mut().right.take();
+
+        // Perform rotation
+        x.borrow_mut().right = Some(y.clone());
+        y.borrow_mut().left = t3;
+
+        // Update heights
+        y.borrow_mut().update(self.refresh);
+        x.borrow_mut().update(self.refresh);
+
+        x
+    }
+    // removes `value`, returning the value that was stored
+    pub fn delete<Q>(&mut self, value: &Q) -> Result<T, TreeError>
+    where T: std::borrow::Borrow<Q>, C: Comparator<Q>, Q: ?Sized
+    {
+        match self.probe(value) {
+            Probe::Found(path, node) => Ok(self.remove_found(path, node)),
+            Probe::Vacant(_) => Err(TreeError::KeyNotFound),
+        }
+    }
+
+    fn min_value_node(&self, node: Rc<RefCell<AVLNode<T>>>) -> Rc<RefCell<AVLNode<T>>> {
+        let mut current = node;
+        while current.borrow().left.is_some() {
+            let next_node = current.borrow().left.clone();
+            if let Some(inner_node) = next_node {
+                current = inner_node;
+            }
+        }
+        current
+    }
+
+    fn max_value_node(&self, node: Rc<RefCell<AVLNode<T>>>) -> Rc<RefCell<AVLNode<T>>> {
+        let mut current = node;
+        while current.borrow().right.is_some() {
+            let next_node = current.borrow().right.clone();
+            if let Some(inner_node) = next_node {
+                current = inner_node;
+            }
+        }
+        current
+    }
+
+    // detaches the leftmost node of the subtree, returning the rebalanced rest
+    fn remove_min_rec(&self, node: Rc<RefCell<AVLNode<T>>>) -> (AVLTreePtr<T>, Rc<RefCell<AVLNode<T>>>) {
+        let left = node.borrow_mut().left.take();
+        match left {
+            Some(left) => {
+                let (rest, min) = self.remove_min_rec(left);
+                node.borrow_mut().left = rest;
+                node.borrow_mut().update(self.refresh);
+                (Some(self.balance(node)), min)
+            },
+            None => {
+                let rest = node.borrow_mut().right.take();
+                node.borrow_mut().update(self.refresh);
+                (rest, node)
+            }
+        }
+    }
+
+    // detaches the rightmost node of the subtree, returning the rebalanced rest
+    fn remove_max_rec(&self, node: Rc<RefCell<AVLNode<T>>>) -> (AVLTreePtr<T>, Rc<RefCell<AVLNode<T>>>) {
+        let right = node.borrow_mut().right.take();
+        match right {
+            Some(right) => {
+                let (rest, max) = self.remove_max_rec(right);
+                node.borrow_mut().right = rest;
+                node.borrow_mut().update(self.refresh);
+                (Some(self.balance(node)), max)
+            },
+            None => {
+                let rest = node.borrow_mut().left.take();
+                node.borrow_mut().update(self.refresh);
+                (rest, node)
+            }
+        }
+    }
+
+    // joins two subtrees and a middle node, where every value in `left` is
+    // less than the middle one and every value in `right` greater. Descends
+    // the taller side until the heights match, so it costs O(|hl - hr| + 1).
+    fn join(&self, left: AVLTreePtr<T>, mid: Rc<RefCell<AVLNode<T>>>, right: AVLTreePtr<T>) -> Rc<RefCell<AVLNode<T>>> {
+        let lh = height(&left);
+        let rh = height(&right);
+        if lh > rh + 1 {
+            let left = left.unwrap();
+            let left_right = left.borrow_mut().right.take();
+            let joined = self.join(left_right, mid, right);
+            left.borrow_mut().right = Some(joined);
+            left.borrow_mut().update(self.refresh);
+            self.balance(left)
+        } else if rh > lh + 1 {
+            let right = right.unwrap();
+            let right_left = right.borrow_mut().left.take();
+            let joined = self.join(left, mid, right_left);
+            right.borrow_mut().left = Some(joined);
+            right.borrow_mut().update(self.refresh);
+            self.balance(right)
+        } else {
+            let mut mid_ref = mid.borrow_mut();
+            mid_ref.left = left;
+            mid_ref.right = right;
+            mid_ref.update(self.refresh);
+            drop(mid_ref);
+            mid
+        }
+    }
+
+    // splits a subtree into the values less than and greater than `value`,
+    // handing back the node holding `value` if there is one
+    fn split_rec(&self, node: AVLTreePtr<T>, value: &T) -> (AVLTreePtr<T>, AVLTreePtr<T>, AVLTreePtr<T>) {
+        let node = match node {
+            Some(node) => node,
+            None => return (None, None, None),
+        };
+        let left = node.borrow_mut().left.take();
+        let right = node.borrow_mut().right.take();
+        let order = self.comparator.compare(value, &node.borrow().value);
+        match order {
+            Ordering::Less => {
+                let (less, found, greater) = self.split_rec(left, value);
+                (less, found, Some(self.join(greater, node, right)))
+            },
+            Ordering::Greater => {
+                let (less, found, greater) = self.split_rec(right, value);
+                (Some(self.join(left, node, less)), found, greater)
+            },
+            Ordering::Equal => (left, Some(node), right),
+        }
+    }
+
+    // splits the tree in two at `value`: self keeps the values less than it
+    // and the returned tree gets the rest, in O(log n)
+    pub fn split_off(&mut self, value: &T) -> Self
+    where C: Clone
+    {
+        let root = self.root.take();
+        let (less, found, greater) = self.split_rec(root, value);
+        self.root = less;
+        let greater = match found {
+            Some(node) => Some(self.join(None, node, greater)),
+            None => greater,
+        };
+        AVLTree { root: greater, refresh: self.refresh, comparator: self.comparator.clone() }
+    }
+
+    // moves every value of `other` into self, leaving `other` empty. When
+    // all values of one tree are less than those of the other the two are
+    // joined in O(log n), otherwise the values are inserted one by one.
+    pub fn append(&mut self, other: &mut Self) {
+        let other_root = match other.root.take() {
+            Some(root) => root,
+            None => return,
+        };
+        let self_root = match self.root.take() {
+            Some(root) => root,
+            None => {
+                self.root = Some(other_root);
+                return;
+            }
+        };
+
+        let self_max = self.max_value_node(self_root.clone()).borrow().value.clone();
+        let self_min = self.min_value_node(self_root.clone()).borrow().value.clone();
+        let other_max = self.max_value_node(other_root.clone()).borrow().value.clone();
+        let other_min = self.min_value_node(other_root.clone()).borrow().value.clone();
+
+        if self.comparator.compare(&self_max, &other_min) == Ordering::Less {
+            let (rest, mid) = self.remove_min_rec(other_root);
+            self.root = Some(self.join(Some(self_root), mid, rest));
+        } else if self.comparator.compare(&other_max, &self_min) == Ordering::Less {
+            let (rest, mid) = self.remove_min_rec(self_root);
+            self.root = Some(self.join(Some(other_root), mid, rest));
+        } else {
+            self.root = Some(self_root);
+            let mut values = Vec::new();
+            into_values_rec(Some(other_root), &mut values);
+            for value in values {
+                self.insert(value);
+            }
+        }
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.root.as_ref().map(|r| self.min_value_node(r.clone()).borrow().value.clone())
+    }
+
+    pub fn max(&self) -> Option<T> {
+        self.root.as_ref().map(|r| self.max_value_node(r.clone()).borrow().value.clone())
+    }
+
+    // smallest value, the same as `min`
+    pub fn first(&self) -> Option<T> {
+        self.min()
+    }
+
+    // largest value, the same as `max`
+    pub fn last(&self) -> Option<T> {
+        self.max()
+    }
+
+    // removes and returns the smallest value, in a single descent
+    pub fn pop_first(&mut self) -> Option<T> {
+        let root = self.root.take()?;
+        let (rest, first) = self.remove_min_rec(root);
+        self.root = rest;
+        Some(take_value(first))
+    }
+
+    // removes and returns the largest value
+    pub fn pop_last(&mut self) -> Option<T> {
+        let root = self.root.take()?;
+        let (rest, last) = self.remove_max_rec(root);
+        self.root = rest;
+        Some(take_value(last))
+    }
+
+    // keeps only the values for which `keep` returns true. The survivors are
+    // rebuilt into a balanced tree in O(n) instead of the others being
+    // deleted one at a time.
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, keep: F) {
+        let mut values = Vec::new();
+        into_values_rec(self.root.take(), &mut values);
+        values.retain(keep);
+        self.root = Self::build_balanced(values.len(), &mut values.into_iter(), self.refresh);
+    }
+
+    // removes every value, handing them out in order
+    pub fn drain(&mut self) -> IntoIter<T> {
+        let mut values = Vec::new();
+        into_values_rec(self.root.take(), &mut values);
+        IntoIter { values: values.into_iter() }
+    }
+
+    // removes the values within `range`, handing them out in order. The
+    // tree is split at both bounds and the outer pieces joined back
+    // together, in O(log n + k) for k removed values.
+    pub fn drain_range<R: RangeBounds<T>>(&mut self, range: R) -> IntoIter<T> {
+        let whole = self.root.take();
+        let (below, rest) = match range.start_bound() {
+            Bound::Included(lower) => self.split_at(whole, lower, false),
+            Bound::Excluded(lower) => self.split_at(whole, lower, true),
+            Bound::Unbounded => (None, whole),
+        };
+        let (drained, above) = match range.end_bound() {
+            Bound::Included(upper) => self.split_at(rest, upper, true),
+            Bound::Excluded(upper) => self.split_at(rest, upper, false),
+            Bound::Unbounded => (rest, None),
+        };
+        let mut values = Vec::new();
+        into_values_rec(drained, &mut values);
+
+        self.root = match above {
+            Some(above) => {
+                let (rest, min) = self.remove_min_rec(above);
+                Some(self.join(below, min, rest))
+            },
+            None => below,
+        };
+        IntoIter { values: values.into_iter() }
+    }
+
+    // removes the values within `range`, returning how many there were
+    pub fn remove_range<R: RangeBounds<T>>(&mut self, range: R) -> usize {
+        self.drain_range(range).len()
+    }
+
+    // splits a subtree at `value`, the node holding `value` going to the
+    // left piece if `found_left` and to the right one otherwise
+    fn split_at(&self, node: AVLTreePtr<T>, value: &T, found_left: bool) -> (AVLTreePtr<T>, AVLTreePtr<T>) {
+        let (less, found, greater) = self.split_rec(node, value);
+        match found {
+            Some(node) if found_left => (Some(self.join(less, node, None)), greater),
+            Some(node) => (less, Some(self.join(None, node, greater))),
+            None => (less, greater),
+        }
+    }
+
+    pub fn count_leaves(&self) -> usize {
+        self.count_leaves_rec(&self.root)
+    }
+
+    fn count_leaves_rec(&self, node: &AVLTreePtr<T>) -> usize {
+        if let Some(curr) = node {
+            let l_count = self.count_leaves_rec(&curr.borrow().left);
+            let r_count = self.count_leaves_rec(&curr.borrow().right);
+            if curr.borrow().left.is_none() && curr.borrow().right.is_none() {
+                return 1;
+            }
+            l_count + r_count
+        } else {
+            0
+        }
+    }
+    pub fn inorder_traversal(&self) -> Vec<T> {
+        let mut result = Vec::new();
+        self.inorder_traversal_rec(&self.root, &mut result);
+        result
+    }
+
+    fn inorder_traversal_rec(&self, node: &AVLTreePtr<T>, result: &mut Vec<T>) {
+        if let Some(curr) = node {
+            self.inorder_traversal_rec(&curr.borrow().left, result);
+            result.push(curr.borrow().value.clone());
+            self.inorder_traversal_rec(&curr.borrow().right, result);
+        }
+    }
+
+    pub fn height(&self) -> isize {
+        self.root.as_ref().map_or(0, |r| r.borrow().height)
+    }
+    pub fn search<Q>(&self, value: &Q) -> bool
+    where T: std::borrow::Borrow<Q>, C: Comparator<Q>, Q: ?Sized
+    {
+        self.search_rec(&self.root, value)
+    }
+
+    fn search_rec<Q>(&self, node: &AVLTreePtr<T>, value: &Q) -> bool
+    where T: std::borrow::Borrow<Q>, C: Comparator<Q>, Q: ?Sized
+    {
+        match node {
+            Some(current_node) => {
+                let order = self.cmp_value(value, &current_node.borrow().value);
+                match order {
+                    Ordering::Equal => true,
+                    Ordering::Less => self.search_rec(&current_node.borrow().left, value),
+                    Ordering::Greater => self.search_rec(&current_node.borrow().right, value),
+                }
+            },
+            None => false,
+        }
+    }
+
+    // returns the stored value equal to `value`, if any
+    pub fn get<Q>(&self, value: &Q) -> Option<T>
+    where T: std::borrow::Borrow<Q>, C: Comparator<Q>, Q: ?Sized
+    {
+        self.find_node(value).map(|node| node.borrow().value.clone())
+    }
+
+    // the node holding `value`, for reading it in place without a clone
+    pub(crate) fn find_node<Q>(&self, value: &Q) -> AVLTreePtr<T>
+    where T: std::borrow::Borrow<Q>, C: Comparator<Q>, Q: ?Sized
+    {
+        let mut current = self.root.clone();
+        while let Some(node) = current {
+            let order = self.cmp_value(value, &node.borrow().value);
+            current = match order {
+                Ordering::Less => node.borrow().left.clone(),
+                Ordering::Greater => node.borrow().right.clone(),
+                Ordering::Equal => return Some(node),
+            };
+        }
+        None
+    }
+
+    // lazy in-order iterator, see `Iter`
+    pub fn iter(&self) -> Iter<'_, T, C> {
+        Iter { range: Range::new(&self.root, &self.comparator, Bound::Unbounded, Bound::Unbounded) }
+    }
+
+    // lazy in-order iterator over the values within `range`
+    pub fn range<R: RangeBounds<T>>(&self, range: R) -> Range<'_, T, C> {
+        Range::new(&self.root, &self.comparator, range.start_bound().cloned(), range.end_bound().cloned())
+    }
+
+    // number of values within `range`, in O(log n) using subtree sizes
+    pub fn count_range<R: RangeBounds<T>>(&self, range: R) -> usize {
+        let upper = match range.end_bound() {
+            Bound::Included(upper) => self.count_below(upper, true),
+            Bound::Excluded(upper) => self.count_below(upper, false),
+            Bound::Unbounded => self.count() as usize,
+        };
+        let lower = match range.start_bound() {
+            Bound::Included(lower) => self.count_below(lower, false),
+            Bound::Excluded(lower) => self.count_below(lower, true),
+            Bound::Unbounded => 0,
+        };
+        upper.saturating_sub(lower)
+    }
+
+    // largest value less than or equal to `value`
+    pub fn floor<Q>(&self, value: &Q) -> Option<T>
+    where T: std::borrow::Borrow<Q>, C: Comparator<Q>, Q: ?Sized
+    {
+        self.closest(value, true, true)
+    }
+
+    // smallest value greater than or equal to `value`
+    pub fn ceiling<Q>(&self, value: &Q) -> Option<T>
+    where T: std::borrow::Borrow<Q>, C: Comparator<Q>, Q: ?Sized
+    {
+        self.closest(value, false, true)
+    }
+
+    // largest value strictly less than `value`
+    pub fn predecessor<Q>(&self, value: &Q) -> Option<T>
+    where T: std::borrow::Borrow<Q>, C: Comparator<Q>, Q: ?Sized
+    {
+        self.closest(value, true, false)
+    }
+
+    // smallest value strictly greater than `value`
+    pub fn successor<Q>(&self, value: &Q) -> Option<T>
+    where T: std::borrow::Borrow<Q>, C: Comparator<Q>, Q: ?Sized
+    {
+        self.closest(value, false, false)
+    }
+
+    // nearest value below (or above) `value` on a single root-to-leaf
+    // descent, `value` itself counting only when `inclusive`
+    fn closest<Q>(&self, value: &Q, below: bool, inclusive: bool) -> Option<T>
+    where T: std::borrow::Borrow<Q>, C: Comparator<Q>, Q: ?Sized
+    {
+        let mut best = None;
+        let mut current = self.root.clone();
+        while let Some(node) = current {
+            let node_ref = node.borrow();
+            // how the stored value compares to `value`
+            let order = self.cmp_value(value, &node_ref.value).reverse();
+            let wanted = if below { Ordering::Less } else { Ordering::Greater };
+            if order == wanted || (inclusive && order == Ordering::Equal) {
+                best = Some(node_ref.value.clone());
+                if order == Ordering::Equal {
+                    break;
+                }
+            }
+            current = if order == Ordering::Greater || (below && order == Ordering::Equal) {
+                node_ref.left.clone()
+            } else {
+                node_ref.right.clone()
+            };
+        }
+        best
+    }
+
+    pub fn cursor_front(&self) -> Cursor<'_, T, C> {
+        let mut path = Vec::new();
+        step_path(&mut path, &self.root, true);
+        Cursor { tree: self, path }
+    }
+
+    pub fn cursor_back(&self) -> Cursor<'_, T, C> {
+        let mut path = Vec::new();
+        step_path(&mut path, &self.root, false);
+        Cursor { tree: self, path }
+    }
+
+    // cursor on `value`, or on the next larger value if it is missing
+    pub fn cursor_at(&self, value: &T) -> Cursor<'_, T, C> {
+        Cursor { path: self.seek_path(value), tree: self }
+    }
+
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T, C> {
+        let mut path = Vec::new();
+        step_path(&mut path, &self.root, true);
+        CursorMut { tree: self, path }
+    }
+
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T, C> {
+        let mut path = Vec::new();
+        step_path(&mut path, &self.root, false);
+        CursorMut { tree: self, path }
+    }
+
+    pub fn cursor_at_mut(&mut self, value: &T) -> CursorMut<'_, T, C> {
+        CursorMut { path: self.seek_path(value), tree: self }
+    }
+
+    // path from the root to the smallest value not less than `value`, empty
+    // if there is none
+    fn seek_path(&self, value: &T) -> Vec<Rc<RefCell<AVLNode<T>>>> {
+        let mut path = Vec::new();
+        let mut found = 0;
+        let mut current = self.root.clone();
+        while let Some(node) = current {
+            let order = self.comparator.compare(value, &node.borrow().value);
+            current = match order {
+                Ordering::Less => node.borrow().left.clone(),
+                Ordering::Greater => node.borrow().right.clone(),
+                Ordering::Equal => None,
+            };
+            path.push(node);
+            if order != Ordering::Greater {
+                found = path.len();
+            }
+        }
+        path.truncate(found);
+        path
+    }
+
+    pub fn print_preorder(&self) -> Vec<T> {
+        let mut result = Vec::new();
+        self.preorder_traversal_rec(&self.root, &mut result);
+        result
+    }
+    
+    fn preorder_traversal_rec(&self, node: &AVLTreePtr<T>, result: &mut Vec<T>) {
+        if let Some(curr) = node {
+            result.push(curr.borrow().value.clone()); // Visit the root node
+            self.preorder_traversal_rec(&curr.borrow().left, result); // Then visit the left subtree
+            self.preorder_traversal_rec(&curr.borrow().right, result); // Finally, visit the right subtree
+        }
+    }
+    
+
+    pub fn print_levelorder(&self) -> Vec<T> {
+        let mut result = Vec::new();
+        if let Some(root) = &self.root {
+            let mut queue = std::collections::VecDeque::new();
+            queue.push_back(root.clone());
+    
+            while !queue.is_empty() {
+                let current = queue.pop_front().unwrap();
+                let current_ref = current.borrow();
+                result.push(current_ref.value.clone());
+    
+                if let Some(left) = &current_ref.left {
+                    queue.push_back(left.clone());
+                }
+    
+                if let Some(right) = &current_ref.right {
+                    queue.push_back(right.clone());
+                }
+            }
+        }
+    
+        result
+    }
+    
+
+    pub fn count(&self) -> u32 {
+        size(&self.root) as u32
+    }
+
+    // k-th smallest value, counting from 0
+    pub fn select(&self, k: usize) -> Option<T> {
+        let mut k = k;
+        let mut current = self.root.clone();
+        while let Some(node) = current {
+            let node_ref = node.borrow();
+            let left_size = size(&node_ref.left);
+            if k < left_size {
+                current = node_ref.left.clone();
+            } else if k > left_size {
+                k -= left_size + 1;
+                current = node_ref.right.clone();
+            } else {
+                return Some(node_ref.value.clone());
+            }
+        }
+        None
+    }
+
+    // number of values strictly less than `value`
+    pub fn rank(&self, value: &T) -> usize {
+        self.count_below(value, false)
+    }
+
+    fn count_below(&self, value: &T, inclusive: bool) -> usize {
+        let mut count = 0;
+        let mut current = self.root.clone();
+        while let Some(node) = current {
+            let node_ref = node.borrow();
+            let order = self.comparator.compare(&node_ref.value, value);
+            if order == Ordering::Less || (inclusive && order == Ordering::Equal) {
+                count += size(&node_ref.left) + 1;
+                current = node_ref.right.clone();
+            } else {
+                current = node_ref.left.clone();
+            }
+        }
+        count
+    }
+
+    // lower median
+    pub fn median(&self) -> Option<T> {
+        let count = self.count() as usize;
+        if count == 0 {
+            return None;
+        }
+        self.select((count - 1) / 2)
+    }
+
+    // nearest-rank percentile, `p` in [0, 100]
+    pub fn percentile(&self, p: f64) -> Option<T> {
+        let count = self.count() as usize;
+        if count == 0 || !(0.0..=100.0).contains(&p) {
+            return None;
+        }
+        let rank = (p / 100.0 * count as f64).ceil() as usize;
+        self.select(rank.saturating_sub(1))
+    }
+
+    // checks ordering, cached heights and sizes, and balance of every node,
+    // reporting the first violation found
+    pub fn validate(&self) -> Result<(), InvariantViolation<T>> {
+        self.validate_rec(&self.root, None, None).map(|_| ())
+    }
+
+    // returns the real height and size of the subtree
+    fn validate_rec(&self, node: &AVLTreePtr<T>, lower: Option<&T>, upper: Option<&T>) -> Result<(isize, usize), InvariantViolation<T>> {
+        let node = match node {
+            Some(node) => node.borrow(),
+            None => return Ok((0, 0)),
+        };
+        let key = &node.value;
+        let out_of_order = lower.is_some_and(|lower| self.comparator.compare(key, lower) != Ordering::Greater)
+            || upper.is_some_and(|upper| self.comparator.compare(key, upper) != Ordering::Less);
+        if out_of_order {
+            return Err(InvariantViolation::OutOfOrder { key: key.clone() });
+        }
+        let (left_height, left_size) = self.validate_rec(&node.left, lower, Some(key))?;
+        let (right_height, right_size) = self.validate_rec(&node.right, Some(key), upper)?;
+        let actual_height = 1 + std::cmp::max(left_height, right_height);
+        if node.height != actual_height {
+            return Err(InvariantViolation::StaleHeight { key: key.clone(), stored: node.height, actual: actual_height });
+        }
+        let balance = left_height - right_height;
+        if balance.abs() > 1 {
+            return Err(InvariantViolation::Unbalanced { key: key.clone(), balance });
+        }
+        let actual_size = 1 + left_size + right_size;
+        if node.size != actual_size {
+            return Err(InvariantViolation::StaleSize { key: key.clone(), stored: node.size, actual: actual_size });
+        }
+        Ok((actual_height, actual_size))
+    }
+}
+
+impl<T: Clone + Display, C> AVLTree<T, C> {
+    pub fn print_tree(&self) {
+        self.print_tree_rec(&self.root, 0);
+    }
+
+    fn print_tree_rec(&self, node: &AVLTreePtr<T>, level: usize) {
+        if let Some(curr) = node {
+            // Print right subtree with increased indentation
+            self.print_tree_rec(&curr.borrow().right, level + 1);
+
+            // Print the current node value with the current level of indentation
+            for _ in 0..level {
+                print!("   ");
+            }
+            println!("{}", curr.borrow().value);
+
+            // Print left subtree with increased indentation
+            self.print_tree_rec(&curr.borrow().left, level + 1);
+        }
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph AVLTree {\n");
+        self.to_dot_rec(&self.root, &mut dot);
+        dot.push_str("}\n");
+        dot
+    }
+    
+    fn to_dot_rec(&self, node: &AVLTreePtr<T>, output: &mut String) {
+        if let Some(curr) = node {
+            let curr_val = format!("{}", curr.borrow().value);
+            if let Some(left) = &curr.borrow().left {
+                let left_val = format!("{}", left.borrow().value);
+                output.push_str(&format!("    {} -> {} [label=\"L\"];\n", curr_val, left_val));
+                self.to_dot_rec(&curr.borrow().left, output);
+            }
+            if let Some(right) = &curr.borrow().right {
+                let right_val = format!("{}", right.borrow().value);
+                output.push_str(&format!("    {} -> {} [label=\"R\"];\n", curr_val, right_val));
+                self.to_dot_rec(&curr.borrow().right, output);
+            }
+        }
+    }
+}
+
+impl<T: Clone, C: Comparator<T> + Default> FromIterator<T> for AVLTree<T, C> {
+    // already sorted input skips the sort and goes straight to the O(n) build
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let comparator = C::default();
+        let mut values: Vec<T> = iter.into_iter().collect();
+        if !values.windows(2).all(|w| comparator.compare(&w[0], &w[1]) != Ordering::Greater) {
+            values.sort_by(|a, b| comparator.compare(a, b));
+        }
+        Self::from_sorted_vec_with(values, comparator)
+    }
+}
+
+impl<T: Clone, C: Comparator<T> + Clone> Extend<T> for AVLTree<T, C> {
+    // sorted input that lies entirely above the current maximum is built
+    // separately and joined on, anything else is inserted value by value
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let values: Vec<T> = iter.into_iter().collect();
+        let cmp = &self.comparator;
+        let sorted = values.windows(2).all(|w| cmp.compare(&w[0], &w[1]) != Ordering::Greater);
+        let above_max = match (self.max(), values.first()) {
+            (Some(max), Some(first)) => cmp.compare(&max, first) == Ordering::Less,
+            _ => true,
+        };
+        if sorted && above_max {
+            let mut other = Self::build_sorted(values, self.comparator.clone(), self.refresh);
+            self.append(&mut other);
+        } else {
+            for value in values {
+                self.insert(value);
+            }
+        }
+    }
+}
+
+/// In-order iterator over an `AVLTree`. Nodes sit behind `RefCell`, so
+/// each value is cloned as it is yielded rather than borrowed; use
+/// `into_iter` to move the values out instead.
+pub struct Iter<'a, T: Clone, C = Natural> {
+    range: Range<'a, T, C>,
+}
+
+impl<'a, T: Clone, C: Comparator<T>> Iterator for Iter<'a, T, C> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.range.next()
+    }
+}
+
+impl<'a, T: Clone, C: Comparator<T>> DoubleEndedIterator for Iter<'a, T, C> {
+    fn next_back(&mut self) -> Option<T> {
+        self.range.next_back()
+    }
+}
+
+/// In-order iterator over the values of an `AVLTree` that fall within a
+/// range, walking both ends with explicit stacks. Only the O(log n) nodes on
+/// the boundary paths are visited besides the ones yielded.
+pub struct Range<'a, T: Clone, C = Natural> {
+    front: Vec<Rc<RefCell<AVLNode<T>>>>,
+    back: Vec<Rc<RefCell<AVLNode<T>>>>,
+    // last nodes handed out from each end, used to detect when they meet
+    last_front: AVLTreePtr<T>,
+    last_back: AVLTreePtr<T>,
+    lower: Bound<T>,
+    upper: Bound<T>,
+    done: bool,
+    cmp: &'a C,
+    _tree: PhantomData<&'a AVLNode<T>>,
+}
+
+impl<'a, T: Clone, C: Comparator<T>> Range<'a, T, C> {
+    fn new(root: &AVLTreePtr<T>, cmp: &'a C, lower: Bound<T>, upper: Bound<T>) -> Self {
+        let mut range = Range {
+            cmp,
+            front: Vec::new(),
+            back: Vec::new(),
+            last_front: None,
+            last_back: None,
+            lower,
+            upper,
+            done: false,
+            _tree: PhantomData,
+        };
+
+        // seed the front with the path to the first value above the lower bound
+        let mut node = root.clone();
+        while let Some(curr) = node {
+            if range.above_lower(&curr.borrow().value) {
+                node = curr.borrow().left.clone();
+                range.front.push(curr);
+            } else {
+                node = curr.borrow().right.clone();
+            }
+        }
+
+        // and the back with the path to the last value below the upper bound
+        let mut node = root.clone();
+        while let Some(curr) = node {
+            if range.below_upper(&curr.borrow().value) {
+                node = curr.borrow().right.clone();
+                range.back.push(curr);
+            } else {
+                node = curr.borrow().left.clone();
+            }
+        }
+        range
+    }
+
+    fn above_lower(&self, value: &T) -> bool {
+        match self.lower {
+            Bound::Included(ref lower) => self.cmp.compare(value, lower) != Ordering::Less,
+            Bound::Excluded(ref lower) => self.cmp.compare(value, lower) == Ordering::Greater,
+            Bound::Unbounded => true,
+        }
+    }
+
+    fn below_upper(&self, value: &T) -> bool {
+        match self.upper {
+            Bound::Included(ref upper) => self.cmp.compare(value, upper) != Ordering::Greater,
+            Bound::Excluded(ref upper) => self.cmp.compare(value, upper) == Ordering::Less,
+            Bound::Unbounded => true,
+        }
+    }
+
+    fn push_left(&mut self, mut node: AVLTreePtr<T>) {
+        while let Some(curr) = node {
+            node = curr.borrow().left.clone();
+            self.front.push(curr);
+        }
+    }
+
+    fn push_right(&mut self, mut node: AVLTreePtr<T>) {
+        while let Some(curr) = node {
+            node = curr.borrow().right.clone();
+            self.back.push(curr);
+        }
+    }
+}
+
+impl<'a, T: Clone, C: Comparator<T>> Iterator for Range<'a, T, C> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.done {
+            return None;
+        }
+        let node = self.front.pop()?;
+        if self.last_back.as_ref().is_some_and(|last| Rc::ptr_eq(last, &node))
+            || !self.below_upper(&node.borrow().value) {
+            self.done = true;
+            return None;
+        }
+        self.push_left(node.borrow().right.clone());
+        let value = node.borrow().value.clone();
+        self.last_front = Some(node);
+        Some(value)
+    }
+}
+
+impl<'a, T: Clone, C: Comparator<T>> DoubleEndedIterator for Range<'a, T, C> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.done {
+            return None;
+        }
+        let node = self.back.pop()?;
+        if self.last_front.as_ref().is_some_and(|last| Rc::ptr_eq(last, &node))
+            || !self.above_lower(&node.borrow().value) {
+            self.done = true;
+            return None;
+        }
+        self.push_right(node.borrow().left.clone());
+        let value = node.borrow().value.clone();
+        self.last_back = Some(node);
+        Some(value)
+    }
+}
+
+impl<'a, T: Clone, C: Comparator<T>> IntoIterator for &'a AVLTree<T, C> {
+    type Item = T;
+    type IntoIter = Iter<'a, T, C>;
+
+    fn into_iter(self) -> Iter<'a, T, C> {
+        self.iter()
+    }
+}
+
+/// Owning iterator over the values of an `AVLTree`, in order.
+pub struct IntoIter<T> {
+    values: std::vec::IntoIter<T>,
+}
+
+impl<T: Clone, C> IntoIterator for AVLTree<T, C> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        let mut values = Vec::new();
+        into_values_rec(self.root, &mut values);
+        IntoIter { values: values.into_iter() }
+    }
+}
+
+// moves a root-to-node path on to the in-order neighbour of its last node:
+// down one side and then as far as possible the other way, or else back up
+// to the first ancestor reached from the near side. An empty path is the
+// ghost position past both ends, from which the walk wraps around.
+fn step_path<T: Clone>(path: &mut Vec<Rc<RefCell<AVLNode<T>>>>, root: &AVLTreePtr<T>, forward: bool) {
+    let down = |node: &Rc<RefCell<AVLNode<T>>>, left: bool| {
+        let node = node.borrow();
+        if left { node.left.clone() } else { node.right.clone() }
+    };
+    let mut node = match path.last() {
+        None => root.clone(),
+        Some(current) => down(current, !forward),
+    };
+    if node.is_none() && !path.is_empty() {
+        let mut child = path.pop().unwrap();
+        while let Some(parent) = path.last() {
+            if down(parent, forward).is_some_and(|near| Rc::ptr_eq(&near, &child)) {
+                return;
+            }
+            child = path.pop().unwrap();
+        }
+        return;
+    }
+    while let Some(curr) = node {
+        node = down(&curr, forward);
+        path.push(curr);
+    }
+}
+
+/// Position among the values of an `AVLTree`, from `AVLTree::cursor_front`,
+/// `cursor_back` or `cursor_at`. AVL nodes have no parent links, so the
+/// cursor keeps the path down from the root and steps to either neighbour
+/// in O(1) amortized. Past either end it rests on a ghost position, from
+/// which `move_next` wraps to the first value and `move_prev` to the last.
+pub struct Cursor<'a, T: Clone, C = Natural> {
+    tree: &'a AVLTree<T, C>,
+    path: Vec<Rc<RefCell<AVLNode<T>>>>,
+}
+
+impl<'a, T: Clone, C> Cursor<'a, T, C> {
+    // value under the cursor, none on the ghost position
+    pub fn current(&self) -> Option<T> {
+        self.path.last().map(|node| node.borrow().value.clone())
+    }
+
+    pub fn move_next(&mut self) {
+        step_path(&mut self.path, &self.tree.root, true);
+    }
+
+    pub fn move_prev(&mut self) {
+        step_path(&mut self.path, &self.tree.root, false);
+    }
+
+    // value the cursor would be on after `move_next`
+    pub fn peek_next(&self) -> Option<T> {
+        let mut path = self.path.clone();
+        step_path(&mut path, &self.tree.root, true);
+        path.last().map(|node| node.borrow().value.clone())
+    }
+
+    pub fn peek_prev(&self) -> Option<T> {
+        let mut path = self.path.clone();
+        step_path(&mut path, &self.tree.root, false);
+        path.last().map(|node| node.borrow().value.clone())
+    }
+}
+
+/// Cursor that can also remove the value it is on, from
+/// `AVLTree::cursor_front_mut`, `cursor_back_mut` or `cursor_at_mut`.
+pub struct CursorMut<'a, T: Clone, C = Natural> {
+    tree: &'a mut AVLTree<T, C>,
+    path: Vec<Rc<RefCell<AVLNode<T>>>>,
+}
+
+impl<'a, T: Clone, C: Comparator<T>> CursorMut<'a, T, C> {
+    // read-only view of the same position
+    pub fn as_cursor(&self) -> Cursor<'_, T, C> {
+        Cursor { tree: self.tree, path: self.path.clone() }
+    }
+
+    pub fn current(&self) -> Option<T> {
+        self.path.last().map(|node| node.borrow().value.clone())
+    }
+
+    pub fn move_next(&mut self) {
+        step_path(&mut self.path, &self.tree.root, true);
+    }
+
+    pub fn move_prev(&mut self) {
+        step_path(&mut self.path, &self.tree.root, false);
+    }
+
+    pub fn peek_next(&self) -> Option<T> {
+        self.as_cursor().peek_next()
+    }
+
+    pub fn peek_prev(&self) -> Option<T> {
+        self.as_cursor().peek_prev()
+    }
+
+    // removes the value under the cursor, which moves on to the next value,
+    // and returns it. The node is unlinked where the path ends and each
+    // ancestor on the path rebalanced on the way back up, so nothing is
+    // searched for from the root.
+    pub fn remove_current(&mut self) -> Result<T, TreeError> {
+        let node = self.path.pop().ok_or(TreeError::KeyNotFound)?;
+        let two_children = node.borrow().left.is_some() && node.borrow().right.is_some();
+        // with two children the node takes over the next value itself,
+        // otherwise the next value is the neighbour stepped to from here
+        let target = if two_children {
+            Some(node.clone())
+        } else {
+            let mut next = self.path.clone();
+            next.push(node.clone());
+            step_path(&mut next, &self.tree.root, true);
+            next.pop()
+        };
+
+        // the path from the next value up to the root of the rebuilt part,
+        // once the next value is inside it
+        let mut upward = Vec::new();
+        let ancestors = std::mem::take(&mut self.path);
+        let mut child = node.clone();
+        let (mut subtree, removed) = self.tree.detach(node);
+        if let (Some(target), Some(root)) = (&target, &subtree) {
+            if two_children || Rc::ptr_eq(target, root) {
+                self.tree.track_down(&mut upward, root.clone(), target);
+            }
+        }
+        for parent in ancestors.into_iter().rev() {
+            {
+                let mut parent_ref = parent.borrow_mut();
+                if parent_ref.left.as_ref().is_some_and(|left| Rc::ptr_eq(left, &child)) {
+                    parent_ref.left = subtree;
+                } else {
+                    parent_ref.right = subtree;
+                }
+                parent_ref.update(self.tree.refresh);
+            }
+            child = parent.clone();
+            let tracked = !upward.is_empty() || target.as_ref().is_some_and(|target| Rc::ptr_eq(target, &parent));
+            let root = self.tree.balance(parent);
+            if let (true, Some(target)) = (tracked, &target) {
+                // a rotation only moves the node it is applied to and the
+                // two below it on the heavy side, so the path is repaired
+                // from the new root down to the first node below those
+                upward.push(child.clone());
+                upward.truncate(upward.len().saturating_sub(3));
+                self.tree.track_down(&mut upward, root.clone(), target);
+            }
+            subtree = Some(root);
+        }
+        self.tree.root = subtree;
+        upward.reverse();
+        self.path = upward;
+        drop(child);
+        Ok(take_value(removed))
+    }
+}
+
+// moves the value out of a detached node, cloning it if the node is shared
+fn take_value<T: Clone>(node: Rc<RefCell<AVLNode<T>>>) -> T {
+    match Rc::try_unwrap(node) {
+        Ok(cell) => cell.into_inner().value,
+        Err(node) => node.borrow().value.clone(),
+    }
+}
+
+// moves values out of nodes that are not shared, cloning the rest
+fn into_values_rec<T: Clone>(node: AVLTreePtr<T>, result: &mut Vec<T>) {
+    if let Some(node) = node {
+        match Rc::try_unwrap(node) {
+            Ok(cell) => {
+                let node = cell.into_inner();
+                into_values_rec(node.left, result);
+                result.push(node.value);
+                into_values_rec(node.right, result);
+            },
+            Err(node) => {
+                into_values_rec(node.borrow().left.clone(), result);
+                result.push(node.borrow().value.clone());
+                into_values_rec(node.borrow().right.clone(), result);
+            }
+        }
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.values.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.values.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.values.next_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T: Clone, C: Comparator<T>> OrderedSet<T> for AVLTree<T, C> {
+    fn insert(&mut self, value: T) -> bool {
+        AVLTree::insert(self, value)
+    }
+
+    fn remove(&mut self, value: &T) -> bool {
+        self.delete(value).is_ok()
+    }
+
+    fn contains(&self, value: &T) -> bool {
+        self.search(value)
+    }
+
+    fn len(&self) -> usize {
+        self.count() as usize
+    }
+
+    fn height(&self) -> usize {
+        AVLTree::height(self) as usize
+    }
+
+    fn count_leaves(&self) -> usize {
+        AVLTree::count_leaves(self)
+    }
+
+    fn min(&self) -> Option<T> {
+        AVLTree::min(self)
+    }
+
+    fn max(&self) -> Option<T> {
+        AVLTree::max(self)
+    }
+
+    fn pop_first(&mut self) -> Option<T> {
+        AVLTree::pop_first(self)
+    }
+
+    fn pop_last(&mut self) -> Option<T> {
+        AVLTree::pop_last(self)
+    }
+
+    fn inorder_traversal(&self) -> Vec<T> {
+        AVLTree::inorder_traversal(self)
+    }
+
+    fn preorder_traversal(&self) -> Vec<T> {
+        self.print_preorder()
+    }
+
+    fn levelorder_traversal(&self) -> Vec<T> {
+        self.print_levelorder()
+    }
+
+    fn validate(&self) -> Result<(), InvariantViolation<T>> {
+        AVLTree::validate(self)
+    }
+}
+
+impl<T: Clone + Display + Debug, C: Comparator<T>> PrintableSet<T> for AVLTree<T, C> {
+    fn print_tree(&self) {
+        AVLTree::print_tree(self)
+    }
+
+    fn to_dot(&self) -> String {
+        AVLTree::to_dot(self)
+    }
+}
@@ -0,0 +1,28 @@
+use std::cmp::Ordering;
+
+/// Ordering used by `AVLTree` and `RBTree` to arrange their keys. Trees
+/// default to `Natural`, the key type's own `Ord`; any
+/// `Fn(&T, &T) -> Ordering` closure or function works as well, so keys can
+/// be ordered by a field, in reverse, or case-insensitively without a
+/// newtype wrapper.
+pub trait Comparator<T: ?Sized> {
+    fn compare(&self, a: &T, b: &T) -> Ordering;
+}
+
+/// Orders keys by their `Ord` implementation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Natural;
+
+impl<T: Ord + ?Sized> Comparator<T> for Natural {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        a.cmp(b)
+    }
+}
+
+impl<T: ?Sized, F> Comparator<T> for F
+where F: Fn(&T, &T) -> Ordering
+{
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        self(a, b)
+    }
+}
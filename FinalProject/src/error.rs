@@ -0,0 +1,23 @@
+use std::error::Error;
+use std::fmt;
+
+/// Why a tree operation could not be carried out. Returned instead of
+/// printing, so callers decide what to tell the user.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TreeError {
+    /// The key is not in the tree.
+    KeyNotFound,
+    /// An interval was given with its start after its end.
+    InvalidInterval,
+}
+
+impl fmt::Display for TreeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TreeError::KeyNotFound => write!(f, "key not found"),
+            TreeError::InvalidInterval => write!(f, "interval start is after its end"),
+        }
+    }
+}
+
+impl Error for TreeError {}
@@ -0,0 +1,198 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::error::TreeError;
+use crate::invariant::InvariantViolation;
+use crate::rbtree::{self, RBTree, Tree};
+
+/// Closed interval `[start, end]` as stored in an `IntervalTree`. Intervals
+/// are ordered by start, then by end.
+#[derive(Clone, Debug)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+    // largest `end` in the subtree under this interval, kept current by the
+    // tree through every rotation
+    max_end: T,
+}
+
+impl<T: Clone> Interval<T> {
+    pub fn new(start: T, end: T) -> Self {
+        Interval { max_end: end.clone(), start, end }
+    }
+}
+
+impl<T: Ord> Interval<T> {
+    // true if the interval shares at least one point with [low, high]
+    pub fn overlaps(&self, low: &T, high: &T) -> bool {
+        self.start <= *high && self.end >= *low
+    }
+
+    pub fn contains(&self, point: &T) -> bool {
+        self.overlaps(point, point)
+    }
+}
+
+impl<T: PartialEq> PartialEq for Interval<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.start == other.start && self.end == other.end
+    }
+}
+
+impl<T: Eq> Eq for Interval<T> {}
+
+impl<T: Ord> PartialOrd for Interval<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord> Ord for Interval<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.start.cmp(&other.start).then_with(|| self.end.cmp(&other.end))
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Interval<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[{}, {}]", self.start, self.end)
+    }
+}
+
+// refresh hook of the underlying tree: an interval's `max_end` is the
+// largest of its own end and its children's `max_end`
+fn refresh_max_end<T: Ord + Clone>(interval: &mut Interval<T>, left: Option<&Interval<T>>, right: Option<&Interval<T>>) {
+    let mut max_end = &interval.end;
+    for child in [left, right].into_iter().flatten() {
+        if child.max_end > *max_end {
+            max_end = &child.max_end;
+        }
+    }
+    interval.max_end = max_end.clone();
+}
+
+/// Set of closed intervals on top of `RBTree`, answering overlap and
+/// stabbing queries in O(min(n, k log n)) for k results. Every node caches
+/// the largest endpoint in its subtree, so whole subtrees that end before a
+/// query are skipped.
+#[derive(Debug)]
+pub struct IntervalTree<T: Ord + Clone> {
+    tree: RBTree<Interval<T>>,
+}
+
+impl<T: Ord + Clone> Default for IntervalTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord + Clone> IntervalTree<T> {
+    pub fn new() -> Self {
+        IntervalTree { tree: RBTree::with_refresh(refresh_max_end) }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tree.count() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    // adds [start, end], returning false if it was already present, or
+    // `InvalidInterval` if start is after end
+    pub fn insert(&mut self, start: T, end: T) -> Result<bool, TreeError> {
+        if start > end {
+            return Err(TreeError::InvalidInterval);
+        }
+        Ok(self.tree.insert(Interval::new(start, end)))
+    }
+
+    // removes [start, end], returning false if it was not present
+    pub fn remove(&mut self, start: T, end: T) -> bool {
+        self.tree.delete(&Interval::new(start, end)).is_ok()
+    }
+
+    pub fn contains(&self, start: T, end: T) -> bool {
+        self.tree.search_element(&Interval::new(start, end))
+    }
+
+    // intervals in order of their start
+    pub fn iter(&self) -> rbtree::Iter<'_, Interval<T>> {
+        self.tree.iter()
+    }
+
+    // all intervals sharing at least one point with [low, high], in order
+    pub fn overlapping(&self, low: T, high: T) -> Vec<Interval<T>> {
+        let mut result = Vec::new();
+        Self::visit_overlapping(self.tree.root(), &low, &high, &mut |interval| result.push(interval.clone()));
+        result
+    }
+
+    // all intervals containing `point`, in order
+    pub fn containing(&self, point: T) -> Vec<Interval<T>> {
+        self.overlapping(point.clone(), point)
+    }
+
+    // number of intervals containing `point`
+    pub fn stabbing_count(&self, point: T) -> usize {
+        let mut count = 0;
+        Self::visit_overlapping(self.tree.root(), &point, &point, &mut |_| count += 1);
+        count
+    }
+
+    // in-order walk over the intervals overlapping [low, high]. A subtree
+    // whose largest endpoint is below `low` cannot hold one, and neither can
+    // anything right of a node starting after `high`.
+    fn visit_overlapping<F: FnMut(&Interval<T>)>(node: &Tree<Interval<T>>, low: &T, high: &T, visit: &mut F) {
+        let node = match node {
+            Some(node) => node.borrow(),
+            None => return,
+        };
+        if node.key.max_end < *low {
+            return;
+        }
+        Self::visit_overlapping(&node.left, low, high, visit);
+        if node.key.start > *high {
+            return;
+        }
+        if node.key.end >= *low {
+            visit(&node.key);
+        }
+        Self::visit_overlapping(&node.right, low, high, visit);
+    }
+
+    // checks the red-black invariants of the underlying tree, then the
+    // cached endpoint of every node
+    pub fn validate(&self) -> Result<(), InvariantViolation<Interval<T>>> {
+        self.tree.validate()?;
+        Self::validate_max_end(self.tree.root()).map(|_| ())
+    }
+
+    // returns the largest endpoint in the subtree
+    fn validate_max_end(node: &Tree<Interval<T>>) -> Result<Option<T>, InvariantViolation<Interval<T>>> {
+        let node = match node {
+            Some(node) => node.borrow(),
+            None => return Ok(None),
+        };
+        let left = Self::validate_max_end(&node.left)?;
+        let right = Self::validate_max_end(&node.right)?;
+        let actual = [left, right].into_iter().flatten().fold(node.key.end.clone(), std::cmp::max);
+        if node.key.max_end != actual {
+            return Err(InvariantViolation::StaleSummary { key: node.key.clone() });
+        }
+        Ok(Some(actual))
+    }
+}
+
+impl<T: Ord + Clone> FromIterator<(T, T)> for IntervalTree<T> {
+    // collecting has no way to report an error, so a pair with its start
+    // after its end panics; use `insert` to handle those
+    fn from_iter<I: IntoIterator<Item = (T, T)>>(iter: I) -> Self {
+        let mut tree = Self::new();
+        for (start, end) in iter {
+            tree.insert(start, end).expect("interval start is after its end");
+        }
+        tree
+    }
+}
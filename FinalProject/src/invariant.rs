@@ -0,0 +1,89 @@
+use std::error::Error;
+use std::fmt::{self, Debug, Display};
+
+/// The first broken invariant found by `AVLTree::validate` or
+/// `RBTree::validate`. Each variant names the key of the node where the
+/// problem was detected.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InvariantViolation<T> {
+    /// `key` is not strictly between the keys of its ancestors.
+    OutOfOrder { key: T },
+    /// The cached AVL height of `key` does not match its children.
+    StaleHeight { key: T, stored: isize, actual: isize },
+    /// The subtrees of `key` differ in height by more than one.
+    Unbalanced { key: T, balance: isize },
+    /// The cached subtree size of `key` does not match its children.
+    StaleSize { key: T, stored: usize, actual: usize },
+    /// The red node `key` has a red child.
+    RedRed { key: T },
+    /// Paths through the left and right subtrees of `key` pass through a
+    /// different number of black nodes.
+    BlackHeightMismatch { key: T, left: usize, right: usize },
+    /// The root `key` is red.
+    RedRoot { key: T },
+    /// The parent pointer of `key` does not point at the node holding it.
+    BrokenParent { key: T },
+    /// The tree's stored key count differs from the number of nodes.
+    CountMismatch { stored: usize, actual: usize },
+    /// The data `key` caches about its subtree, such as the largest endpoint
+    /// below an interval, does not match its children.
+    StaleSummary { key: T },
+}
+
+impl<T> InvariantViolation<T> {
+    // the same violation with its key converted by `f`, for trees that store
+    // keys wrapped with extra data
+    pub fn map_key<U, F: FnOnce(T) -> U>(self, f: F) -> InvariantViolation<U> {
+        match self {
+            InvariantViolation::OutOfOrder { key } => InvariantViolation::OutOfOrder { key: f(key) },
+            InvariantViolation::StaleHeight { key, stored, actual } => InvariantViolation::StaleHeight { key: f(key), stored, actual },
+            InvariantViolation::Unbalanced { key, balance } => InvariantViolation::Unbalanced { key: f(key), balance },
+            InvariantViolation::StaleSize { key, stored, actual } => InvariantViolation::StaleSize { key: f(key), stored, actual },
+            InvariantViolation::RedRed { key } => InvariantViolation::RedRed { key: f(key) },
+            InvariantViolation::BlackHeightMismatch { key, left, right } => InvariantViolation::BlackHeightMismatch { key: f(key), left, right },
+            InvariantViolation::RedRoot { key } => InvariantViolation::RedRoot { key: f(key) },
+            InvariantViolation::BrokenParent { key } => InvariantViolation::BrokenParent { key: f(key) },
+            InvariantViolation::CountMismatch { stored, actual } => InvariantViolation::CountMismatch { stored, actual },
+            InvariantViolation::StaleSummary { key } => InvariantViolation::StaleSummary { key: f(key) },
+        }
+    }
+}
+
+impl<T: Display> Display for InvariantViolation<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InvariantViolation::OutOfOrder { key } => {
+                write!(f, "key {} is out of order", key)
+            },
+            InvariantViolation::StaleHeight { key, stored, actual } => {
+                write!(f, "node {} stores height {} but has height {}", key, stored, actual)
+            },
+            InvariantViolation::Unbalanced { key, balance } => {
+                write!(f, "node {} has balance factor {}", key, balance)
+            },
+            InvariantViolation::StaleSize { key, stored, actual } => {
+                write!(f, "node {} stores size {} but has size {}", key, stored, actual)
+            },
+            InvariantViolation::RedRed { key } => {
+                write!(f, "red node {} has a red child", key)
+            },
+            InvariantViolation::BlackHeightMismatch { key, left, right } => {
+                write!(f, "node {} has black height {} on the left and {} on the right", key, left, right)
+            },
+            InvariantViolation::RedRoot { key } => {
+                write!(f, "root {} is red", key)
+            },
+            InvariantViolation::BrokenParent { key } => {
+                write!(f, "node {} has a wrong parent pointer", key)
+            },
+            InvariantViolation::CountMismatch { stored, actual } => {
+                write!(f, "tree count is {} but it holds {} nodes", stored, actual)
+            },
+            InvariantViolation::StaleSummary { key } => {
+                write!(f, "node {} caches stale subtree data", key)
+            },
+        }
+    }
+}
+
+impl<T: Debug + Display> Error for InvariantViolation<T> {}
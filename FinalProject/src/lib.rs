@@ -1,2 +1,24 @@
-pub mod rbtree;
-pub mod avltree;
\ No newline at end of file
+#![allow(non_snake_case)]
+
+pub mod rbtree;
+pub mod avltree;
+pub mod ordered_set;
+pub mod set_ops;
+pub mod map;
+pub mod avlmap;
+pub mod rbmap;
+pub mod multiset;
+pub mod avlmultiset;
+pub mod rbmultiset;
+pub mod interval_tree;
+pub mod avlsummary;
+pub mod rbsummary;
+pub mod priority_deque;
+pub mod invariant;
+pub mod error;
+pub mod comparator;
+pub mod summary;
+pub mod arena_avltree;
+pub mod arena_rbtree;
+mod arena;
+mod map_slots;
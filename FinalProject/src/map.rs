@@ -0,0 +1,204 @@
+use std::marker::PhantomData;
+
+use crate::map_slots::{Located, MapKey, SlotTree, Slots};
+
+/// Ordered map on top of either tree: keys are kept balanced by the tree,
+/// each one pointing at its value in a side table. Used through `AVLMap` and
+/// `RBMap`.
+#[derive(Debug)]
+pub struct Map<K, V, S> {
+    tree: S,
+    slots: Slots<V>,
+    _key: PhantomData<K>,
+}
+
+impl<K: Ord + Clone, V, S: SlotTree<K>> Default for Map<K, V, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord + Clone, V, S: SlotTree<K>> Map<K, V, S> {
+    pub fn new() -> Self {
+        Map {
+            tree: S::default(),
+            slots: Slots::new(),
+            _key: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // insert a key-value pair, returning the value previously stored under key
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self.entry(key) {
+            Entry::Occupied(mut entry) => Some(entry.insert(value)),
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+                None
+            }
+        }
+    }
+
+    // the entry for `key`, found in a single descent whether or not the key
+    // is present
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, S> {
+        match self.tree.locate(&key) {
+            Located::Found(found, slot) => Entry::Occupied(OccupiedEntry { map: self, key, found, slot }),
+            Located::Vacant(vacant) => Entry::Vacant(VacantEntry { map: self, key, vacant }),
+        }
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.tree.slot(key).map(|slot| self.slots.get(slot))
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        match self.tree.slot(key) {
+            Some(slot) => Some(self.slots.get_mut(slot)),
+            None => None,
+        }
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.tree.slot(key).is_some()
+    }
+
+    // remove a key, returning its value if it was present
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        match self.tree.locate(key) {
+            Located::Found(found, _) => {
+                let entry = self.tree.remove_at(found);
+                Some(self.slots.remove(entry.slot))
+            },
+            Located::Vacant(_) => None,
+        }
+    }
+
+    // keys in ascending order
+    pub fn keys(&self) -> Vec<K> {
+        self.tree.entries().map(|entry| entry.key).collect()
+    }
+}
+
+/// A view into a single key of a `Map`, from `Map::entry`.
+pub enum Entry<'a, K, V, S: SlotTree<K>> {
+    Occupied(OccupiedEntry<'a, K, V, S>),
+    Vacant(VacantEntry<'a, K, V, S>),
+}
+
+/// An entry whose key is in the map, holding on to its node so that
+/// removing it does not search again.
+pub struct OccupiedEntry<'a, K, V, S: SlotTree<K>> {
+    map: &'a mut Map<K, V, S>,
+    key: K,
+    found: S::Found,
+    slot: usize,
+}
+
+/// An entry whose key is missing, remembering where the descent ended so
+/// that inserting it does not search again.
+pub struct VacantEntry<'a, K, V, S: SlotTree<K>> {
+    map: &'a mut Map<K, V, S>,
+    key: K,
+    vacant: S::Vacant,
+}
+
+impl<'a, K: Ord + Clone, V, S: SlotTree<K>> Entry<'a, K, V, S> {
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    // the value under the key, inserting `default` first if there is none
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        self.or_insert_with_key(|_| default())
+    }
+
+    pub fn or_insert_with_key<F: FnOnce(&K) -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let value = default(entry.key());
+                entry.insert(value)
+            }
+        }
+    }
+
+    pub fn or_default(self) -> &'a mut V
+    where V: Default
+    {
+        self.or_insert_with(V::default)
+    }
+
+    // runs `f` on the value if the key is present
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, K: Ord + Clone, V, S: SlotTree<K>> OccupiedEntry<'a, K, V, S> {
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    pub fn get(&self) -> &V {
+        self.map.slots.get(self.slot)
+    }
+
+    pub fn get_mut(&mut self) -> &mut V {
+        self.map.slots.get_mut(self.slot)
+    }
+
+    // the value, borrowed for as long as the map was
+    pub fn into_mut(self) -> &'a mut V {
+        self.map.slots.get_mut(self.slot)
+    }
+
+    // replaces the value, returning the old one
+    pub fn insert(&mut self, value: V) -> V {
+        self.map.slots.replace(self.slot, value)
+    }
+
+    // removes the key from the map, returning its value
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+
+    pub fn remove_entry(self) -> (K, V) {
+        let entry = self.map.tree.remove_at(self.found);
+        (entry.key, self.map.slots.remove(entry.slot))
+    }
+}
+
+impl<'a, K: Ord + Clone, V, S: SlotTree<K>> VacantEntry<'a, K, V, S> {
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    // inserts the key with `value`, returning the value
+    pub fn insert(self, value: V) -> &'a mut V {
+        let slot = self.map.slots.insert(value);
+        self.map.tree.insert_at(self.vacant, MapKey { key: self.key, slot });
+        self.map.slots.get_mut(slot)
+    }
+}
@@ -0,0 +1,198 @@
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::fmt;
+use std::rc::Rc;
+
+use crate::avltree::{self, AVLNode, AVLTree, ProbePath};
+use crate::rbtree::{self, Node, RBTree, Tree, TreeNode};
+
+// Key stored inside the tree of a map. Only `key` takes part in ordering, and
+// the tree is searched with a plain `&K` through `Borrow`. Public, like
+// `SlotTree`, only so that it can appear in the public map types; this
+// module is private, so it cannot be named outside the crate.
+#[derive(Clone, Copy, Debug)]
+pub struct MapKey<K> {
+    pub(crate) key: K,
+    pub(crate) slot: usize,
+}
+
+impl<K: PartialEq> PartialEq for MapKey<K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl<K: Eq> Eq for MapKey<K> {}
+
+impl<K: PartialOrd> PartialOrd for MapKey<K> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.key.partial_cmp(&other.key)
+    }
+}
+
+impl<K: Ord> Ord for MapKey<K> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+// lets a tree of entries be searched with a plain `&K`
+impl<K> std::borrow::Borrow<K> for MapKey<K> {
+    fn borrow(&self) -> &K {
+        &self.key
+    }
+}
+
+impl<K: fmt::Display> fmt::Display for MapKey<K> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.key.fmt(f)
+    }
+}
+
+// Values of a map live here rather than in the tree nodes, so that they can
+// be borrowed without going through the nodes' `RefCell`s.
+#[derive(Clone, Debug)]
+pub(crate) struct Slots<V> {
+    values: Vec<Option<V>>,
+    free: Vec<usize>,
+}
+
+impl<V> Slots<V> {
+    pub(crate) fn new() -> Self {
+        Slots { values: Vec::new(), free: Vec::new() }
+    }
+
+    pub(crate) fn insert(&mut self, value: V) -> usize {
+        match self.free.pop() {
+            Some(slot) => {
+                self.values[slot] = Some(value);
+                slot
+            },
+            None => {
+                self.values.push(Some(value));
+                self.values.len() - 1
+            }
+        }
+    }
+
+    pub(crate) fn remove(&mut self, slot: usize) -> V {
+        let value = self.values[slot].take().expect("map slot is vacant");
+        self.free.push(slot);
+        value
+    }
+
+    pub(crate) fn get(&self, slot: usize) -> &V {
+        self.values[slot].as_ref().expect("map slot is vacant")
+    }
+
+    pub(crate) fn get_mut(&mut self, slot: usize) -> &mut V {
+        self.values[slot].as_mut().expect("map slot is vacant")
+    }
+
+    pub(crate) fn replace(&mut self, slot: usize, value: V) -> V {
+        std::mem::replace(self.get_mut(slot), value)
+    }
+}
+
+// Outcome of `SlotTree::locate`: the key's node along with its slot, or
+// where the key would be linked.
+pub enum Located<F, V> {
+    Found(F, usize),
+    Vacant(V),
+}
+
+// What the maps and multisets need from the tree holding their keys, so that
+// one implementation of each serves both trees.
+pub trait SlotTree<K>: Default {
+    // a node from `locate`, enough to unlink it without searching again
+    type Found;
+    // where `locate` found that a missing key would be linked
+    type Vacant;
+    type Iter<'a>: DoubleEndedIterator<Item = MapKey<K>> where Self: 'a;
+
+    fn len(&self) -> usize;
+
+    // slot of `key`, read in place without cloning the stored key
+    fn slot(&self, key: &K) -> Option<usize>;
+
+    // looks `key` up in a single descent
+    fn locate(&self, key: &K) -> Located<Self::Found, Self::Vacant>;
+
+    fn insert_at(&mut self, vacant: Self::Vacant, key: MapKey<K>);
+
+    fn remove_at(&mut self, found: Self::Found) -> MapKey<K>;
+
+    fn entries(&self) -> Self::Iter<'_>;
+}
+
+impl<K: Ord + Clone> SlotTree<K> for AVLTree<MapKey<K>> {
+    type Found = (ProbePath<MapKey<K>>, Rc<RefCell<AVLNode<MapKey<K>>>>);
+    type Vacant = ProbePath<MapKey<K>>;
+    type Iter<'a> = avltree::Iter<'a, MapKey<K>> where K: 'a;
+
+    fn len(&self) -> usize {
+        self.count() as usize
+    }
+
+    fn slot(&self, key: &K) -> Option<usize> {
+        self.find_node(key).map(|node| node.borrow().value.slot)
+    }
+
+    fn locate(&self, key: &K) -> Located<Self::Found, Self::Vacant> {
+        match self.probe(key) {
+            avltree::Probe::Found(path, node) => {
+                let slot = node.borrow().value.slot;
+                Located::Found((path, node), slot)
+            },
+            avltree::Probe::Vacant(path) => Located::Vacant(path),
+        }
+    }
+
+    fn insert_at(&mut self, vacant: Self::Vacant, key: MapKey<K>) {
+        self.insert_vacant(vacant, key);
+    }
+
+    fn remove_at(&mut self, (path, node): Self::Found) -> MapKey<K> {
+        self.remove_found(path, node)
+    }
+
+    fn entries(&self) -> Self::Iter<'_> {
+        self.iter()
+    }
+}
+
+impl<K: Ord + Clone> SlotTree<K> for RBTree<MapKey<K>> {
+    type Found = TreeNode<MapKey<K>>;
+    type Vacant = (Tree<MapKey<K>>, bool);
+    type Iter<'a> = rbtree::Iter<'a, MapKey<K>> where K: 'a;
+
+    fn len(&self) -> usize {
+        self.count() as usize
+    }
+
+    fn slot(&self, key: &K) -> Option<usize> {
+        self.search(key).map(|node| node.borrow().key.slot)
+    }
+
+    fn locate(&self, key: &K) -> Located<Self::Found, Self::Vacant> {
+        match self.probe(key) {
+            rbtree::Probe::Found(node) => {
+                let slot = node.borrow().key.slot;
+                Located::Found(node, slot)
+            },
+            rbtree::Probe::Vacant { parent, go_left } => Located::Vacant((parent, go_left)),
+        }
+    }
+
+    fn insert_at(&mut self, (parent, go_left): Self::Vacant, key: MapKey<K>) {
+        self.insert_vacant(parent, go_left, Node::new(key).unwrap());
+    }
+
+    fn remove_at(&mut self, found: Self::Found) -> MapKey<K> {
+        self.take_node(found)
+    }
+
+    fn entries(&self) -> Self::Iter<'_> {
+        self.iter()
+    }
+}
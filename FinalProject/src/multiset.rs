@@ -0,0 +1,183 @@
+use std::marker::PhantomData;
+
+use crate::map_slots::{Located, MapKey, SlotTree, Slots};
+
+/// Ordered multiset on top of either tree: each distinct key is stored once
+/// in the tree, pointing at its multiplicity in a side table. Used through
+/// `AVLMultiset` and `RBMultiset`.
+#[derive(Debug)]
+pub struct Multiset<T, S> {
+    tree: S,
+    counts: Slots<usize>,
+    len: usize,
+    _key: PhantomData<T>,
+}
+
+impl<T: Ord + Clone, S: SlotTree<T>> Default for Multiset<T, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord + Clone, S: SlotTree<T>> Multiset<T, S> {
+    pub fn new() -> Self {
+        Multiset {
+            tree: S::default(),
+            counts: Slots::new(),
+            len: 0,
+            _key: PhantomData,
+        }
+    }
+
+    // number of keys, counting every duplicate
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    // number of distinct keys
+    pub fn distinct_len(&self) -> usize {
+        self.tree.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // adds one occurrence of key, returning how many there are now. The key
+    // is looked up and, if new, linked in a single descent.
+    pub fn insert(&mut self, key: T) -> usize {
+        self.len += 1;
+        match self.tree.locate(&key) {
+            Located::Found(_, slot) => {
+                let count = self.counts.get_mut(slot);
+                *count += 1;
+                *count
+            },
+            Located::Vacant(vacant) => {
+                let slot = self.counts.insert(1);
+                self.tree.insert_at(vacant, MapKey { key, slot });
+                1
+            }
+        }
+    }
+
+    pub fn count_of(&self, key: &T) -> usize {
+        self.tree.slot(key).map_or(0, |slot| *self.counts.get(slot))
+    }
+
+    pub fn contains(&self, key: &T) -> bool {
+        self.tree.slot(key).is_some()
+    }
+
+    // removes one occurrence of key, returning false if there was none
+    pub fn remove_one(&mut self, key: &T) -> bool {
+        match self.tree.locate(key) {
+            Located::Found(found, slot) => {
+                if *self.counts.get(slot) > 1 {
+                    *self.counts.get_mut(slot) -= 1;
+                } else {
+                    self.tree.remove_at(found);
+                    self.counts.remove(slot);
+                }
+                self.len -= 1;
+                true
+            },
+            Located::Vacant(_) => false,
+        }
+    }
+
+    // removes every occurrence of key, returning how many there were
+    pub fn remove_all(&mut self, key: &T) -> usize {
+        match self.tree.locate(key) {
+            Located::Found(found, slot) => {
+                self.tree.remove_at(found);
+                let count = self.counts.remove(slot);
+                self.len -= count;
+                count
+            },
+            Located::Vacant(_) => 0,
+        }
+    }
+
+    // keys in ascending order, each repeated as often as it was inserted
+    pub fn iter(&self) -> Iter<'_, T, S> {
+        Iter {
+            entries: self.tree.entries(),
+            counts: &self.counts,
+            front: None,
+            back: None,
+        }
+    }
+}
+
+impl<T: Ord + Clone, S: SlotTree<T>> FromIterator<T> for Multiset<T, S> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T: Ord + Clone, S: SlotTree<T>> Extend<T> for Multiset<T, S> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for key in iter {
+            self.insert(key);
+        }
+    }
+}
+
+/// In-order iterator over a `Multiset`, yielding each key once per
+/// occurrence.
+pub struct Iter<'a, T: 'a, S: SlotTree<T> + 'a> {
+    entries: S::Iter<'a>,
+    counts: &'a Slots<usize>,
+    // key being repeated at each end and how many copies it has left
+    front: Option<(T, usize)>,
+    back: Option<(T, usize)>,
+}
+
+impl<'a, T: Ord + Clone, S: SlotTree<T>> Iterator for Iter<'a, T, S> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.front.is_none() {
+            // once the tree is exhausted the back end may still hold copies
+            self.front = match self.entries.next() {
+                Some(entry) => Some((entry.key, *self.counts.get(entry.slot))),
+                None => self.back.take(),
+            };
+        }
+        let (key, remaining) = self.front.as_mut()?;
+        *remaining -= 1;
+        if *remaining > 0 {
+            return Some(key.clone());
+        }
+        self.front.take().map(|(key, _)| key)
+    }
+}
+
+impl<'a, T: Ord + Clone, S: SlotTree<T>> DoubleEndedIterator for Iter<'a, T, S> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.back.is_none() {
+            self.back = match self.entries.next_back() {
+                Some(entry) => Some((entry.key, *self.counts.get(entry.slot))),
+                None => self.front.take(),
+            };
+        }
+        let (key, remaining) = self.back.as_mut()?;
+        *remaining -= 1;
+        if *remaining > 0 {
+            return Some(key.clone());
+        }
+        self.back.take().map(|(key, _)| key)
+    }
+}
+
+impl<'a, T: Ord + Clone, S: SlotTree<T>> IntoIterator for &'a Multiset<T, S> {
+    type Item = T;
+    type IntoIter = Iter<'a, T, S>;
+
+    fn into_iter(self) -> Iter<'a, T, S> {
+        self.iter()
+    }
+}
@@ -0,0 +1,68 @@
+use std::fmt::Debug;
+
+use crate::invariant::InvariantViolation;
+
+/// Operations shared by every ordered set in this crate, so callers can be
+/// written once and switch between `AVLTree` and `RBTree`, either generically
+/// or through a `Box<dyn OrderedSet<T>>`. Nothing here formats keys, so any
+/// key type will do; drawing the tree is left to `PrintableSet`.
+pub trait OrderedSet<T> {
+    /// Inserts `value`, returning `false` if it was already present.
+    fn insert(&mut self, value: T) -> bool;
+
+    /// Removes `value`, returning `false` if it was not present.
+    fn remove(&mut self, value: &T) -> bool;
+
+    fn contains(&self, value: &T) -> bool;
+
+    /// Number of keys stored in the set.
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of levels in the tree, `0` when empty.
+    fn height(&self) -> usize;
+
+    fn count_leaves(&self) -> usize;
+
+    fn min(&self) -> Option<T>;
+
+    fn max(&self) -> Option<T>;
+
+    /// Removes and returns the smallest key. The default looks it up and
+    /// removes it in two descents; the trees override it with one.
+    fn pop_first(&mut self) -> Option<T> {
+        let first = self.min()?;
+        self.remove(&first);
+        Some(first)
+    }
+
+    /// Removes and returns the largest key.
+    fn pop_last(&mut self) -> Option<T> {
+        let last = self.max()?;
+        self.remove(&last);
+        Some(last)
+    }
+
+    fn inorder_traversal(&self) -> Vec<T>;
+
+    fn preorder_traversal(&self) -> Vec<T>;
+
+    fn levelorder_traversal(&self) -> Vec<T>;
+
+    /// Checks the structural invariants of the tree, returning the first
+    /// violation found.
+    fn validate(&self) -> Result<(), InvariantViolation<T>>;
+}
+
+/// An `OrderedSet` whose keys can be printed, so that it can also draw its
+/// tree.
+pub trait PrintableSet<T>: OrderedSet<T> + Debug {
+    /// Prints the tree structure to stdout.
+    fn print_tree(&self);
+
+    /// Graphviz DOT representation of the tree.
+    fn to_dot(&self) -> String;
+}
@@ -0,0 +1,123 @@
+use std::cmp::Ordering;
+use std::fmt::{self, Debug, Display};
+use std::marker::PhantomData;
+
+use crate::avltree::AVLTree;
+use crate::ordered_set::OrderedSet;
+use crate::rbtree::RBTree;
+
+/// Item as stored in a `PriorityDeque`: the item itself, followed by the
+/// order in which it was pushed so that equal items stay distinct keys.
+#[derive(Clone, Debug)]
+pub struct Queued<T> {
+    item: T,
+    seq: u64,
+}
+
+impl<T: PartialEq> PartialEq for Queued<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.item == other.item && self.seq == other.seq
+    }
+}
+
+impl<T: Eq> Eq for Queued<T> {}
+
+impl<T: Ord> PartialOrd for Queued<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord> Ord for Queued<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.item.cmp(&other.item).then(self.seq.cmp(&other.seq))
+    }
+}
+
+impl<T: Display> Display for Queued<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.item.fmt(f)
+    }
+}
+
+/// Double-ended priority queue on top of any `OrderedSet`, handing out both
+/// its smallest and its largest item in O(log n). Equal items leave the low
+/// end oldest first and the high end newest first.
+pub struct PriorityDeque<T, S> {
+    set: S,
+    pushed: u64,
+    _item: PhantomData<T>,
+}
+
+/// `PriorityDeque` on an `RBTree`.
+pub type RBPriorityDeque<T> = PriorityDeque<T, RBTree<Queued<T>>>;
+
+/// `PriorityDeque` on an `AVLTree`.
+pub type AVLPriorityDeque<T> = PriorityDeque<T, AVLTree<Queued<T>>>;
+
+impl<T, S: Debug> Debug for PriorityDeque<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PriorityDeque").field("set", &self.set).finish()
+    }
+}
+
+impl<T, S: OrderedSet<Queued<T>> + Default> Default for PriorityDeque<T, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, S: OrderedSet<Queued<T>> + Default> PriorityDeque<T, S> {
+    pub fn new() -> Self {
+        PriorityDeque { set: S::default(), pushed: 0, _item: PhantomData }
+    }
+}
+
+impl<T, S: OrderedSet<Queued<T>>> PriorityDeque<T, S> {
+    pub fn len(&self) -> usize {
+        self.set.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+
+    pub fn push(&mut self, item: T) {
+        self.set.insert(Queued { item, seq: self.pushed });
+        self.pushed += 1;
+    }
+
+    // smallest item, without removing it
+    pub fn peek_min(&self) -> Option<T> {
+        self.set.min().map(|queued| queued.item)
+    }
+
+    // largest item, without removing it
+    pub fn peek_max(&self) -> Option<T> {
+        self.set.max().map(|queued| queued.item)
+    }
+
+    pub fn pop_min(&mut self) -> Option<T> {
+        self.set.pop_first().map(|queued| queued.item)
+    }
+
+    pub fn pop_max(&mut self) -> Option<T> {
+        self.set.pop_last().map(|queued| queued.item)
+    }
+}
+
+impl<T, S: OrderedSet<Queued<T>> + Default> FromIterator<T> for PriorityDeque<T, S> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut deque = Self::new();
+        deque.extend(iter);
+        deque
+    }
+}
+
+impl<T, S: OrderedSet<Queued<T>>> Extend<T> for PriorityDeque<T, S> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
@@ -0,0 +1,16 @@
+use crate::rbtree::RBTree;
+use crate::map;
+use crate::map_slots::MapKey;
+
+/// Ordered map on top of `RBTree`: keys are kept balanced by the tree, each
+/// one pointing at its value in a side table.
+pub type RBMap<K, V> = map::Map<K, V, RBTree<MapKey<K>>>;
+
+/// A view into a single key of an `RBMap`, from `RBMap::entry`.
+pub type Entry<'a, K, V> = map::Entry<'a, K, V, RBTree<MapKey<K>>>;
+
+/// An entry whose key is in the map.
+pub type OccupiedEntry<'a, K, V> = map::OccupiedEntry<'a, K, V, RBTree<MapKey<K>>>;
+
+/// An entry whose key is missing.
+pub type VacantEntry<'a, K, V> = map::VacantEntry<'a, K, V, RBTree<MapKey<K>>>;
@@ -0,0 +1,11 @@
+use crate::rbtree::RBTree;
+use crate::map_slots::MapKey;
+use crate::multiset;
+
+/// Ordered multiset on top of `RBTree`: each distinct key is stored once in
+/// the tree, pointing at its multiplicity in a side table.
+pub type RBMultiset<T> = multiset::Multiset<T, RBTree<MapKey<T>>>;
+
+/// In-order iterator over an `RBMultiset`, yielding each key once per
+/// occurrence.
+pub type Iter<'a, T> = multiset::Iter<'a, T, RBTree<MapKey<T>>>;
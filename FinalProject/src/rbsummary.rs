@@ -0,0 +1,121 @@
+use std::ops::{Bound, RangeBounds};
+
+use crate::invariant::InvariantViolation;
+use crate::rbtree::{RBTree, Tree};
+use crate::summary::{above_lower, below_upper, refresh_summary, Annotated, Summary};
+
+/// Ordered set on top of `RBTree` whose nodes cache the `Summary` of their
+/// subtree, kept current through every rotation and fix-up, so that the
+/// summary of any key range comes out in O(log n).
+#[derive(Debug)]
+pub struct RBSummaryTree<T: Ord, S> {
+    tree: RBTree<Annotated<T, S>>,
+}
+
+impl<T: Ord + Clone, S: Summary<T>> Default for RBSummaryTree<T, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord + Clone, S: Summary<T>> RBSummaryTree<T, S> {
+    pub fn new() -> Self {
+        RBSummaryTree { tree: RBTree::with_refresh(refresh_summary) }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tree.count() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    // returns false if the key was already present
+    pub fn insert(&mut self, key: T) -> bool {
+        self.tree.insert(Annotated::new(key))
+    }
+
+    // returns false if the key was not present
+    pub fn remove(&mut self, key: &T) -> bool {
+        self.tree.delete(key).is_ok()
+    }
+
+    pub fn contains(&self, key: &T) -> bool {
+        self.tree.search_element(key)
+    }
+
+    // keys in ascending order
+    pub fn keys(&self) -> Vec<T> {
+        self.tree.iter().map(|entry| entry.key).collect()
+    }
+
+    // summary of every key, read off the root
+    pub fn total(&self) -> S {
+        self.tree.root().as_ref().map_or_else(S::identity, |root| root.borrow().key.summary.clone())
+    }
+
+    // summary of the keys within `range`, in O(log n)
+    pub fn aggregate<R: RangeBounds<T>>(&self, range: R) -> S {
+        Self::aggregate_rec(self.tree.root(), range.start_bound(), range.end_bound())
+    }
+
+    // once a node falls inside the range, its left subtree is bounded only
+    // from below and its right one only from above, so apart from the two
+    // boundary paths every subtree is taken whole from its cached summary
+    fn aggregate_rec(node: &Tree<Annotated<T, S>>, lower: Bound<&T>, upper: Bound<&T>) -> S {
+        let node = match node {
+            Some(node) => node.borrow(),
+            None => return S::identity(),
+        };
+        if let (Bound::Unbounded, Bound::Unbounded) = (lower, upper) {
+            return node.key.summary.clone();
+        }
+        let key = &node.key.key;
+        if !above_lower(key, lower) {
+            return Self::aggregate_rec(&node.right, lower, upper);
+        }
+        if !below_upper(key, upper) {
+            return Self::aggregate_rec(&node.left, lower, upper);
+        }
+        let left = Self::aggregate_rec(&node.left, lower, Bound::Unbounded);
+        let right = Self::aggregate_rec(&node.right, Bound::Unbounded, upper);
+        left.combine(&S::of(key)).combine(&right)
+    }
+
+    // checks the red-black invariants of the underlying tree, then the
+    // cached summary of every node
+    pub fn validate(&self) -> Result<(), InvariantViolation<T>>
+    where S: PartialEq
+    {
+        self.tree.validate().map_err(|violation| violation.map_key(|entry| entry.key))?;
+        Self::validate_summaries(self.tree.root()).map(|_| ())
+    }
+
+    // returns the real summary of the subtree
+    fn validate_summaries(node: &Tree<Annotated<T, S>>) -> Result<S, InvariantViolation<T>>
+    where S: PartialEq
+    {
+        let node = match node {
+            Some(node) => node.borrow(),
+            None => return Ok(S::identity()),
+        };
+        let left = Self::validate_summaries(&node.left)?;
+        let right = Self::validate_summaries(&node.right)?;
+        let actual = left.combine(&S::of(&node.key.key)).combine(&right);
+        if node.key.summary != actual {
+            return Err(InvariantViolation::StaleSummary { key: node.key.key.clone() });
+        }
+        Ok(actual)
+    }
+}
+
+impl<T: Ord + Clone, S: Summary<T>> FromIterator<T> for RBSummaryTree<T, S> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut tree = Self::new();
+        for key in iter {
+            tree.insert(key);
+        }
+        tree
+    }
+}
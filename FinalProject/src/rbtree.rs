@@ -1,768 +1,2158 @@
-use std::cell::RefCell;
-use std::rc::Rc;
-use std::mem::replace;
-use std::fmt::Debug;
-use std::fmt::Display;
-use std::fmt;
-use std::collections::VecDeque;
-
-#[allow(non_snake_case)]
-
-#[derive(Clone, Debug, PartialEq, Copy)]
-enum NodeColor {
-    Red,
-    Black,
-}
-
-type TreeNode<T> = Rc<RefCell<Node<T>>>;
-type Tree<T> = Option<TreeNode<T>>;
-
-#[derive(Clone)]
-pub struct Node<T: Ord+Display+Debug> {
-    color: NodeColor,
-    key: T,
-    parent: Tree<T>,
-    left: Tree<T>,
-    right: Tree<T>,
-}
-
-impl<T> Node<T>
-where 
-    T: Debug+Ord+Display+Copy
-{
-    pub fn new(key: T) -> Tree<T> {
-        Some(Rc::new(RefCell::new(Node {
-            color: NodeColor::Red,
-            key: key,
-            parent: None,
-            left: None,
-            right: None,
-        })))
-    }
-}
-
-impl<T> fmt::Debug for Node<T>
-where T: Debug+Ord+Display+Copy
-{
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        f.debug_struct("Node")
-         .field("color", &self.color)
-         .field("key", &self.key)
-         .field("right", &self.right)
-         .field("left", &self.left)
-         .finish()
-    }
-}
-
-enum Direction {
-    Left,
-    Right
-}
-
-#[derive(Clone, Debug)]
-pub struct RBTree<T: Ord+Display+Debug+Copy> {
-    root: Tree<T>,
-    count: u32,
-}
-
-impl<T> RBTree<T>
-where T: Ord+Display+Debug+Clone+Copy
-{
-    pub fn new() -> Self {
-        RBTree {
-            root: None,
-            count: 0,
-        }
-    }
-
-    pub fn count(&self) -> u32 {
-        self.count
-    }
-
-    // check if tree is empty
-    pub fn is_empty(&self) -> bool {
-        if self.root.is_none() {
-            return true;
-        } else {
-            return false;
-        }
-    }
-
-    // insert a node to the red-black tree
-    pub fn insert(&mut self, key: T) {
-        if self.search(key).is_none() {
-            let root = replace(&mut self.root, None);
-            let updated_tree = self.insert_node(root, key);
-            self.root = self.insert_fix(updated_tree.1);
-        } else {
-            println!("Key already in tree");
-        }
-    }
-
-    fn insert_node(&mut self, tree: Tree<T>, key: T) -> (Tree<T>,TreeNode<T>) {
-        match tree {
-            Some(tree_node) => {
-                let sub_tree: TreeNode<T>;
-                let node_clone = tree_node.borrow().clone();
-                if key < node_clone.key {
-                    let res = self.insert_node(node_clone.left, key);
-                    let res_tree = res.0;
-                    sub_tree = res.1;
-                    res_tree.as_ref().unwrap().borrow_mut().parent = Some(tree_node.clone());
-                    tree_node.borrow_mut().left = res_tree;
-                } else {
-                    let res = self.insert_node(node_clone.right, key);
-                    let res_tree = res.0;
-                    sub_tree = res.1;
-                    res_tree.as_ref().unwrap().borrow_mut().parent = Some(tree_node.clone());
-                    tree_node.borrow_mut().right = res_tree;
-                };
-                (Some(tree_node),sub_tree)
-            },
-            None => {
-                self.count += 1;
-                let added_node = Node::<T>::new(key);
-                (added_node.clone(),added_node.unwrap())
-            }
-        }
-    }
-
-    fn insert_fix(&mut self, tree_node: TreeNode<T>) -> Tree<T> {
-        let mut is_root = tree_node.borrow().parent.is_none(); 
-        let root = if is_root {
-            Some(tree_node)
-        } else {
-            let mut node = tree_node.clone();
-            let mut parent_clone = tree_node.borrow().parent.as_ref().unwrap().borrow().clone();
-            let mut parent_color = parent_clone.color;
-            
-            while !is_root && parent_color == NodeColor::Red {
-                let node_clone = node.borrow().clone();
-                let uncle_return = match node_clone.parent {
-                    Some(parent) => {
-                        let parent = parent.borrow().clone();
-                        match parent.parent {
-                            Some(grandparent) => {
-                                let grandparent = grandparent.borrow().clone();
-                                if grandparent.key < parent.key {
-                                    Some((grandparent.left.clone(), Direction::Left))
-                                } else {
-                                    Some((grandparent.right.clone(), Direction::Right))
-                                }
-                            },
-                            None => {None}
-                        }
-                    },
-                    None => { None }
-                };
-                
-                match uncle_return {
-                    Some(uncle) => {
-                        let uncle_node = uncle.0;
-                        let side = uncle.1;
-
-                        match side {
-                            Direction::Right => {
-                                let mut parent = node.borrow().parent.as_ref().unwrap().clone();
-                                if uncle_node.is_some() && uncle_node.as_ref().unwrap().borrow().color == NodeColor::Red {
-                                    parent.borrow_mut().color = NodeColor::Black;
-                                    uncle_node.unwrap().borrow_mut().color = NodeColor::Black;
-                                    parent.borrow().parent.as_ref().unwrap().borrow_mut().color = NodeColor::Red;
-                                    node = parent.borrow().clone().parent.clone().unwrap();
-                                } else {
-                                    if parent.borrow().clone().key < node.borrow().clone().key {
-                                        let parent_tmp = node.borrow().parent.as_ref().unwrap().clone();
-                                        node = parent_tmp;
-                                        self.rotate_left(node.clone());
-                                        parent = node.borrow().parent.as_ref().unwrap().clone();
-                                    } 
-
-                                    parent.borrow_mut().color = NodeColor::Black;
-                                    parent.borrow().parent.as_ref().unwrap().borrow_mut().color = NodeColor::Red;
-                                    let grandparent = node.borrow().parent.as_ref().unwrap().borrow().parent.as_ref().unwrap().clone();
-                                    self.rotate_right(grandparent);
-                                }
-                            },
-                            Direction::Left => {
-                                let mut parent = node.borrow().parent.as_ref().unwrap().clone();
-                                if uncle_node.is_some() && uncle_node.as_ref().unwrap().borrow().color == NodeColor::Red {
-                                    parent.borrow_mut().color = NodeColor::Black;
-                                    uncle_node.unwrap().borrow_mut().color = NodeColor::Black;
-                                    parent.borrow().parent.as_ref().unwrap().borrow_mut().color = NodeColor::Red;
-                                    node = parent.borrow().clone().parent.clone().unwrap();
-                                } else {
-                                    if parent.borrow().clone().key > node.borrow().clone().key {
-                                        let parent_tmp = node.borrow().parent.as_ref().unwrap().clone();
-                                        node = parent_tmp;
-                                        self.rotate_right(node.clone());
-                                        parent = node.borrow().parent.as_ref().unwrap().clone();
-                                    }
-                                    parent.borrow_mut().color = NodeColor::Black;
-                                    parent.borrow().parent.as_ref().unwrap().borrow_mut().color = NodeColor::Red;
-                                    let grandparent = node.borrow().parent.as_ref().unwrap().borrow().parent.as_ref().unwrap().clone();
-                                    self.rotate_left(grandparent);
-                                }
-                            }
-                        }
-                    },
-                    None => {
-                        break;
-                    }
-                }
-                is_root = node.borrow().parent.is_none();
-                if !is_root {
-                    parent_clone = node.borrow().parent.as_ref().unwrap().borrow().clone();
-                    parent_color = parent_clone.color;
-                }
-            } 
-
-            while node.borrow().parent.is_some() {
-                let p = node.borrow().parent.as_ref().unwrap().clone();
-                node = p;
-            }
-            Some(node)
-        };
-        root.as_ref().unwrap().borrow_mut().color = NodeColor::Black;
-        root
-    }
-
-    
-    fn rotate_left(&mut self, tree_node: TreeNode<T>) {
-        let cur_parent = tree_node;
-        let right_child = cur_parent.borrow().right.clone();
-    
-        cur_parent.borrow_mut().right = right_child.as_ref().and_then(|n| n.borrow().left.clone());
-    
-        if let Some(ref right_child) = right_child {
-            if let Some(ref right_child_left) = right_child.borrow().left {
-                right_child_left.borrow_mut().parent = Some(cur_parent.clone());
-            }
-        }
-    
-        if let Some(ref right_child) = right_child {
-            right_child.borrow_mut().parent = cur_parent.borrow().parent.clone();
-        }
-    
-        match cur_parent.borrow().parent.clone() {
-            Some(ref grandparent) => {
-                let left_child_ptr = grandparent.borrow().left.as_ref().map(|n| n.as_ptr()).unwrap_or(std::ptr::null_mut());
-                let cur_parent_ptr = cur_parent.as_ptr();
-                
-                if std::ptr::eq(left_child_ptr, cur_parent_ptr) {
-                    grandparent.borrow_mut().left = right_child.clone();
-                } else {
-                    grandparent.borrow_mut().right = right_child.clone();
-                }
-            },
-            None => {
-                self.root = right_child.clone();
-            },
-        }
-    
-        if let Some(ref right_child) = right_child {
-            right_child.borrow_mut().left = Some(cur_parent.clone());
-        }
-        cur_parent.borrow_mut().parent = right_child;
-    }
-    
-    
-
-    fn rotate_right(&self, tree_node: TreeNode<T>) {
-        let cur_parent = tree_node;
-        let left_child = cur_parent.borrow().left.clone();
-
-        cur_parent.borrow_mut().left = match left_child {
-            Some(ref left_child) => {left_child.borrow().right.clone()},
-            None => {None}
-        };
-
-        if left_child.is_some() {
-            left_child.as_ref().unwrap().borrow_mut().parent = cur_parent.borrow().parent.clone();
-            if left_child.as_ref().unwrap().borrow().right.is_some() {
-                let r = left_child.as_ref().unwrap().borrow().right.clone();
-                r.unwrap().borrow_mut().parent = Some(cur_parent.clone());
-            }
-        }
-
-        match cur_parent.borrow().clone().parent {
-            Some(grandparent) => {
-                if grandparent.borrow().clone().key < cur_parent.borrow().clone().key {
-                    grandparent.borrow_mut().right = left_child.clone();
-                } else {
-                    grandparent.borrow_mut().left = left_child.clone();
-                }
-            },
-            None => {
-                left_child.as_ref().unwrap().borrow_mut().parent = None;
-            },
-        }
-        left_child.as_ref().unwrap().borrow_mut().right = Some(cur_parent.clone());
-        cur_parent.borrow_mut().parent = left_child.clone();
-    }
-
-    pub fn search(&self, key: T) -> Tree<T> {
-        let dummy = Node::<T>::new(key).unwrap().borrow().clone();
-        self.search_node(&self.root, &dummy)
-    }
-
-    fn search_node(&self, tree_node: &Tree<T>, node: &Node<T>) -> Tree<T> {
-        match tree_node {
-            Some(sub_tree) => {
-                let sub_tree_clone = sub_tree.borrow().clone();
-                if sub_tree_clone.key == node.key {
-                    Some(sub_tree.clone())
-                } else {
-                    if sub_tree_clone.key > node.key {
-                        self.search_node(&sub_tree_clone.left, node)
-                    } else {
-                        self.search_node(&sub_tree_clone.right, node)
-                    }
-                }
-            },
-            None => {None}
-        }
-    }
-
-    pub fn search_element(&self, key: T) -> bool {
-        self.search_element_rec(&self.root, key)
-    }
-
-    // Helper function to recursively search for a node
-    fn search_element_rec(&self, tree_node: &Tree<T>, key: T) -> bool {
-        match tree_node {
-            Some(node) => {
-                let node_ref = node.borrow();
-                if node_ref.key == key {
-                    true
-                } else if key < node_ref.key {
-                    self.search_element_rec(&node_ref.left, key)
-                } else {
-                    self.search_element_rec(&node_ref.right, key)
-                }
-            },
-            None => false,
-        }
-    }
-
-    // delete a node from the red-black tree
-    pub fn delete(&mut self, key: T) {
-        let z = self.search(key);
-        if z.is_none() {
-            println!("Key not found");
-            return;
-        }
-        let u = z; 
-        let p = u.as_ref().unwrap().borrow().parent.clone();
-        let v = u.as_ref().unwrap().borrow().left.clone(); 
-        let w = u.as_ref().unwrap().borrow().right.clone();
-
-        let mut side = Direction::Left; 
-
-        if p.is_some() {
-            side = if p.as_ref().unwrap().borrow().clone().key > u.as_ref().unwrap().borrow().clone().key {
-                Direction::Right
-            } else {
-                Direction::Left
-            };
-        }
-
-        let mut u_original_color = u.as_ref().unwrap().borrow().color.clone();
-        let x: Tree<T>;
-
-        if v.is_none() {
-            x = w.clone();
-            self.transplant(u.clone(),w.clone());
-            self.print_tree();
-        } else if w.is_none() {
-            x = v.clone();
-            self.transplant(u.clone(), v.clone());
-        } else {
-            let y = self.find_min(w.clone());
-            u_original_color = y.as_ref().unwrap().borrow().color.clone();
-            x = y.as_ref().unwrap().borrow().clone().right;
-            if y.as_ref().unwrap().borrow().clone().parent.as_ref().unwrap().borrow().clone().key == u.as_ref().unwrap().borrow().clone().key {
-                if x.is_some() {
-                    x.as_ref().unwrap().borrow_mut().parent = y.clone();
-                }
-            } else {
-                self.transplant(y.clone(), y.as_ref().unwrap().borrow().right.clone());
-                y.as_ref().unwrap().borrow_mut().right = u.as_ref().unwrap().borrow().right.clone();
-                y.as_ref().unwrap().borrow().right.as_ref().unwrap().borrow_mut().parent = y.clone();
-            }
-            self.transplant(u.clone(), y.clone());
-            y.as_ref().unwrap().borrow_mut().left = v.clone();
-            v.as_ref().unwrap().borrow_mut().parent = y.clone();
-            y.as_ref().unwrap().borrow_mut().color = u.as_ref().unwrap().borrow().color.clone();
-        }
-        if u_original_color == NodeColor::Black {
-            self.delete_fix(x.clone(), p.clone(), side);
-        }
-        self.count -= 1;
-    }
-
-    fn delete_fix(&mut self, x: Tree<T>, p: Tree<T>, side: Direction) {
-        self.print_tree();
-        let mut x_color = if x.is_some() {
-            x.as_ref().unwrap().borrow().clone().color == NodeColor::Black
-        } else {
-            true
-        };
-        let mut cur_p = p;
-        let mut cur_x = x;
-        let mut is_root = cur_p.is_none();
-        while !is_root && x_color {
-            match side {
-                Direction::Right => {
-                    self.print_tree();
-                    let mut s = cur_p.as_ref().unwrap().borrow().right.clone();
-                    if s.is_some() {
-                        self.print_tree();
-                        if s.as_ref().unwrap().borrow().clone().color == NodeColor::Red {
-                            s.as_ref().unwrap().borrow_mut().color = NodeColor::Black;
-                            cur_p.as_ref().unwrap().borrow_mut().color = NodeColor::Red;
-                            self.rotate_left(cur_p.as_ref().unwrap().clone());
-                            s = cur_p.as_ref().unwrap().borrow().right.clone();
-                        }
-                        let s_left = s.as_ref().unwrap().borrow().clone().left.clone();
-                        let s_right = s.as_ref().unwrap().borrow().clone().right.clone();
-
-                        let s_left_color = if s_left.is_some() {
-                            s_left.as_ref().unwrap().borrow().clone().color == NodeColor::Black
-                        } else {
-                            true
-                        };
-
-                        let s_right_color = if s_right.is_some() {
-                            s_right.as_ref().unwrap().borrow().clone().color == NodeColor::Black
-                        } else {
-                            true
-                        };
-
-                        if s_left_color && s_right_color {
-                            s.as_ref().unwrap().borrow_mut().color = NodeColor::Red;
-                            cur_x = cur_p.clone();
-                            let g = cur_p.as_ref().unwrap().borrow().clone().parent.clone();
-                            cur_p = g.clone();
-                            x_color = if cur_x.is_some() {
-                                cur_x.as_ref().unwrap().borrow().clone().color == NodeColor::Black
-                            } else {
-                                true
-                            };
-                        } else {
-                            self.print_tree();
-                            if s_right.is_some() && s_right.as_ref().unwrap().borrow().clone().color == NodeColor::Black {
-                                if s_left.is_some() {
-                                    s_left.as_ref().unwrap().borrow_mut().color = NodeColor::Black;
-                                    s.as_ref().unwrap().borrow_mut().color = NodeColor::Red;
-                                    self.rotate_right(s.unwrap());
-                                    s = cur_p.as_ref().unwrap().borrow().right.clone();
-                                }
-                            }
-                            s.as_ref().unwrap().borrow_mut().color = cur_p.as_ref().unwrap().borrow().color.clone();
-                            cur_p.as_ref().unwrap().borrow_mut().color = NodeColor::Black;
-                            if s_right.is_some() {
-                                self.print_tree();
-                                s_right.as_ref().unwrap().borrow_mut().color = NodeColor::Black;
-                            }
-                            self.rotate_left(cur_p.as_ref().unwrap().clone());
-                            is_root = true;
-                            self.print_tree();
-                        }
-                    }
-                },
-                Direction::Left => {
-                    let mut s = cur_p.as_ref().unwrap().borrow().left.clone();
-                    if s.is_some() {
-                        if s.as_ref().unwrap().borrow().clone().color == NodeColor::Red {
-                            s.as_ref().unwrap().borrow_mut().color = NodeColor::Black;
-                            cur_p.as_ref().unwrap().borrow_mut().color = NodeColor::Red;
-                            self.rotate_right(cur_p.as_ref().unwrap().clone());
-                            s = cur_p.as_ref().unwrap().borrow().left.clone();
-                        }
-                        let s_left = s.as_ref().unwrap().borrow().clone().left.clone();
-                        let s_right = s.as_ref().unwrap().borrow().clone().right.clone();
-
-                        let s_left_color = if s_left.is_some() {
-                            s_left.as_ref().unwrap().borrow().clone().color == NodeColor::Black
-                        } else {
-                            true
-                        };
-
-                        let s_right_color = if s_right.is_some() {
-                            s_right.as_ref().unwrap().borrow().clone().color == NodeColor::Black
-                        } else {
-                            true
-                        };
-
-                        if s_left_color && s_right_color {
-                            s.as_ref().unwrap().borrow_mut().color = NodeColor::Red;
-                            cur_x = cur_p.clone();
-                            let g = cur_p.as_ref().unwrap().borrow().clone().parent.clone();
-                            cur_p = g.clone();
-                            x_color = if cur_x.is_some() {
-                                cur_x.as_ref().unwrap().borrow().clone().color == NodeColor::Black
-                            } else {
-                                true
-                            };
-                        } else {
-                            if s_right.is_some() && s_right.as_ref().unwrap().borrow().clone().color == NodeColor::Black {
-                                if s_left.is_some() {
-                                    s_left.as_ref().unwrap().borrow_mut().color = NodeColor::Black;
-                                    s.as_ref().unwrap().borrow_mut().color = NodeColor::Red;
-                                    self.rotate_left(s.unwrap());
-                                    s = cur_p.as_ref().unwrap().borrow().left.clone();
-                                }
-                            }
-                            s.as_ref().unwrap().borrow_mut().color = cur_p.as_ref().unwrap().borrow().color.clone();
-                            cur_p.as_ref().unwrap().borrow_mut().color = NodeColor::Black;
-                            if s_left.is_some() {
-                                s_left.as_ref().unwrap().borrow_mut().color = NodeColor::Black;
-                            }
-                            self.rotate_right(cur_p.as_ref().unwrap().clone());
-                            is_root = true;
-                        }
-                    }
-                }
-            }
-        }
-        if cur_x.is_some() {
-            cur_x.as_ref().unwrap().borrow_mut().color = NodeColor::Black;
-        }
-    }
-    
-
-    fn transplant(&mut self, z: Tree<T>, v: Tree<T>) {
-        let u = z.unwrap();
-        let u_p = u.borrow().parent.clone();
-        if u_p.is_none() {
-            self.root = v.clone();
-        } else {
-            if u_p.as_ref().unwrap().borrow().clone().key > u.borrow().clone().key {
-                u_p.as_ref().unwrap().borrow_mut().left = v.clone();
-            } else {
-                u_p.as_ref().unwrap().borrow_mut().right = v.clone();
-            }
-        }
-        if v.is_some() {
-            v.as_ref().unwrap().borrow_mut().parent = u_p.clone();
-        }
-    }
-
-    fn find_min(&self, tree: Tree<T>) -> Tree<T> {
-        match tree {
-            Some(sub_tree) => {
-                let mut left = Some(sub_tree.clone());
-                while left.as_ref().unwrap().borrow().left.clone().is_some() {
-                    left = left.unwrap().borrow().left.clone();
-                }
-                left
-            },
-            None => {
-                tree
-            }
-        }
-    }
-
-    fn find_max(&self, tree: Tree<T>) -> Tree<T> {
-        match tree {
-            Some(sub_tree) => {
-                let mut right = Some(sub_tree.clone());
-                while right.as_ref().unwrap().borrow().right.clone().is_some() {
-                    right = right.unwrap().borrow().right.clone();
-                }
-                right
-            },
-            None => {
-                tree
-            }
-        }
-    }
-
-    // count the number of leaves in a tree
-    pub fn leaves(&self) -> u32 {
-        if self.root.is_none() {
-            return 0;
-        }
-        let root = self.root.as_ref().unwrap().clone();
-        let mut stack: Vec<Tree<T>> = Vec::new();
-        stack.push(Some(root));
-
-        let mut count = 0;
-        while !stack.is_empty() {
-            let node = stack.pop();
-            let mut node_left = None;
-            let mut node_right = None;
-
-            if node.is_some() {
-                node_left = node.as_ref().unwrap().as_ref().unwrap().borrow().clone().left.clone();
-                node_right = node.as_ref().unwrap().as_ref().unwrap().borrow().clone().right.clone();
-            }
-
-            if node_left.is_some() {
-                stack.push(node_left.clone());
-            }
-
-            if node_right.is_some() {
-                stack.push(node_right.clone());
-            }
-
-            if node_left.is_none() && node_right.is_none() {
-                count += 1;
-            }
-        }
-        count
-    }
-
-    // 4- return the height of a tree
-    pub fn height(&self) -> u32 {
-        if self.root.is_none() {
-            return 0;
-        }
-        let root = self.root.as_ref().unwrap().clone();
-        let mut queue: VecDeque<Tree<T>> = VecDeque::new();
-        queue.push_back(Some(root));
-
-        let mut height = 0;
-        while !queue.is_empty() {
-            let n = queue.len();
-            for _ in 0..n {
-                let node = queue.pop_front().unwrap().unwrap();
-                for child in [node.borrow().left.clone(), node.borrow().right.clone()] {
-                    if child.is_some() {
-                        queue.push_back(child);
-                    }
-                }
-            }
-            height += 1;
-        }
-        height
-    }
-
-    // print in-order traversal of tree
-    pub fn print_inorder(&self) {
-        if self.root.is_none() {
-            println!("None");
-            return;
-        }
-        let mut root = self.root.clone();
-        let mut stack: Vec<Tree<T>> = Vec::new();
-        while !stack.is_empty() || !root.is_none() {
-            if root.is_some() {
-                stack.push(root.clone());
-                let p = root.as_ref().unwrap().borrow().left.clone();
-                root = p.clone();
-            } else {
-                let pop = stack.pop().unwrap();
-                print!(" {} ", pop.as_ref().unwrap().borrow().key.clone());
-                root = pop.as_ref().unwrap().borrow().right.clone();
-            }
-        }
-        println!("\n");
-    }
-
-    pub fn print_preorder(&self) {
-        if self.root.is_none() {
-            println!("None");
-            return;
-        }
-        let mut root = self.root.clone();
-        let mut stack: Vec<Tree<T>> = Vec::new();
-        stack.push(root);
-        let mut cur: Tree<T>;
-        while !stack.is_empty() {
-            cur = stack.pop().unwrap();
-            root = cur.clone();
-            print!(" {} ", root.as_ref().unwrap().borrow().key.clone());
-            let root_right = root.as_ref().unwrap().borrow().right.clone();
-            let root_left = root.as_ref().unwrap().borrow().left.clone();
-            if root_right.is_some() {
-                stack.push(root_right.clone());
-            }
-            if root_left.is_some() {
-                stack.push(root_left.clone());
-            }
-        }
-        println!("\n");
-    }
-
-    pub fn print_levelorder(&self) {
-        if self.root.is_none() {
-            println!("None");
-            return;
-        };
-        let inorder_nodes = self.inorder();
-        for node in inorder_nodes {
-            print!(" {} ", node.unwrap().borrow().key.clone());
-        }
-        println!("\n");
-    }
-
-    pub fn min(&self) -> Tree<T> {
-        self.find_min(self.root.clone())
-    }
-
-    pub fn max(&self) -> Tree<T> {
-        self.find_max(self.root.clone())
-    }
-
-    fn inorder(&self) -> VecDeque<Tree<T>> {
-        let root = self.root.as_ref().unwrap().clone();
-        let mut queue: VecDeque<Tree<T>> = VecDeque::new();
-        queue.push_back(Some(root));
-        let mut res: VecDeque<Tree<T>> = VecDeque::new();
-        while !queue.is_empty() {
-            let n = queue.len();
-            for _ in 0..n {
-                let node = queue.pop_front().unwrap().unwrap();
-                res.push_back(Some(node.clone()));
-                for child in [node.borrow().left.clone(), node.borrow().right.clone()] {
-                    if child.is_some() {
-                        queue.push_back(child);
-                    }
-                }
-            }
-        }
-        res
-    }
-
-    pub fn print_tree(&self) {
-        self.print_node(&self.root, 0, "Root", "");
-    }
-
-    fn print_node(&self, node: &Tree<T>, depth: usize, node_type: &str, prefix: &str) {
-        if let Some(ref node) = node {
-            let color = match node.borrow().color {
-                NodeColor::Black => "black",
-                NodeColor::Red => "red",
-            };
-
-            let new_prefix = if node_type == "Root" {
-                "── "
-            } else {
-                if node_type == "L" { "├── " } else { "└── " }
-            };
-
-            println!("{}{}[{}] {}: {}", prefix, new_prefix, color, node_type, node.borrow().key);
-
-            let child_prefix = if node_type == "L" {
-                format!("{}│   ", prefix)
-            } else {
-                format!("{}    ", prefix)
-            };
-
-            self.print_node(&node.borrow().left, depth + 1, "L", &child_prefix);
-            self.print_node(&node.borrow().right, depth + 1, "R", &child_prefix);
-        }
-    }
-}
-
-impl<T> fmt::Display for RBTree<T>
-where T: Debug+Ord+Display+Copy
-{
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        f.debug_struct("RBTree")
-         .field("root", &self.root)
-         .field("length", &self.count)
-         .finish()
-    }
-}
\ No newline at end of file
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::fmt;
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+use std::ops::{Bound, RangeBounds};
+
+use crate::comparator::{Comparator, Natural};
+use crate::error::TreeError;
+use crate::invariant::InvariantViolation;
+use crate::ordered_set::{OrderedSet, PrintableSet};
+use crate::set_ops::{Difference, Intersection, SymmetricDifference, Union};
+use crate::summary::Refresh;
+
+#[cfg(feature = "debug_print")]
+macro_rules! debug_println {
+    ($($args:tt)*)=>{
+        println!($($args)*);
+    };
+}
+
+#[cfg(not(feature = "debug_print"))]
+macro_rules! debug_println {
+    ($($args:tt)*)=>{};
+}
+
+#[allow(non_snake_case)]
+
+#[derive(Clone, Debug, PartialEq, Copy)]
+enum NodeColor {
+    Red,
+    Black,
+}
+
+pub(crate) type TreeNode<T> = Rc<RefCell<Node<T>>>;
+pub(crate) type Tree<T> = Option<TreeNode<T>>;
+
+#[derive(Clone)]
+pub struct Node<T> {
+    color: NodeColor,
+    pub(crate) key: T,
+    // weak so that parent and child do not keep each other alive
+    parent: Option<Weak<RefCell<Node<T>>>>,
+    pub(crate) left: Tree<T>,
+    pub(crate) right: Tree<T>,
+    // number of nodes in the subtree rooted here
+    size: usize,
+}
+
+impl<T> Node<T> {
+    pub fn new(key: T) -> Tree<T> {
+        Some(Rc::new(RefCell::new(Node {
+            color: NodeColor::Red,
+            key,
+            parent: None,
+            left: None,
+            right: None,
+            size: 1,
+        })))
+    }
+
+    fn parent(&self) -> Tree<T> {
+        self.parent.as_ref().and_then(Weak::upgrade)
+    }
+}
+
+fn size<T>(node: &Tree<T>) -> usize {
+    node.as_ref().map_or(0, |n| n.borrow().size)
+}
+
+// recomputes the size of `node` from its children
+fn update_size<T>(node: &TreeNode<T>) {
+    let new_size = 1 + size(&node.borrow().left) + size(&node.borrow().right);
+    node.borrow_mut().size = new_size;
+}
+
+// moves the key out of an unlinked node, cloning it if the node is still
+// shared through a handle from `search`
+fn take_key<T: Clone>(node: TreeNode<T>) -> T {
+    match Rc::try_unwrap(node) {
+        Ok(cell) => cell.into_inner().key,
+        Err(shared) => shared.borrow().key.clone(),
+    }
+}
+
+// unlinks the nodes of a detached tree in order and moves their keys out,
+// cloning only the keys of nodes still shared through a handle from `search`
+fn take_keys<T: Clone>(root: Tree<T>, count: usize) -> Vec<T> {
+    let mut keys = Vec::with_capacity(count);
+    let mut stack: Vec<TreeNode<T>> = Vec::new();
+    let mut current = root;
+    loop {
+        while let Some(node) = current {
+            current = node.borrow_mut().left.take();
+            stack.push(node);
+        }
+        let node = match stack.pop() {
+            Some(node) => node,
+            None => break,
+        };
+        current = node.borrow_mut().right.take();
+        keys.push(take_key(node));
+    }
+    keys
+}
+
+impl<T> fmt::Debug for Node<T>
+where T: Debug
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Node")
+         .field("color", &self.color)
+         .field("key", &self.key)
+         .field("right", &self.right)
+         .field("left", &self.left)
+         .finish()
+    }
+}
+
+enum Direction {
+    Left,
+    Right
+}
+
+// Outcome of a single descent looking for a key: the node holding it, or the
+// node a new leaf for it would hang from (none in an empty tree) and on which
+// side.
+pub(crate) enum Probe<T> {
+    Found(TreeNode<T>),
+    Vacant { parent: Tree<T>, go_left: bool },
+}
+
+/// Hook called at each step of `RBTree::delete` with the tree as it stands
+/// and the name of the step, e.g. `|tree, _| tree.print_tree()` to watch the
+/// fix-up while debugging.
+pub type DeleteTrace<T, C = Natural> = fn(&RBTree<T, C>, &'static str);
+
+/// Red-black tree ordered by the comparator `C`, which defaults to the
+/// keys' own `Ord`; see `RBTree::with_comparator` for anything else.
+#[derive(Clone)]
+pub struct RBTree<T, C = Natural> {
+    root: Tree<T>,
+    count: u32,
+    delete_trace: Option<DeleteTrace<T, C>>,
+    refresh: Option<Refresh<T>>,
+    comparator: C,
+}
+
+impl<T, C> Default for RBTree<T, C>
+where C: Default
+{
+    fn default() -> Self {
+        Self::with_comparator(C::default())
+    }
+}
+
+impl<T, C> fmt::Debug for RBTree<T, C>
+where T: Debug
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RBTree")
+         .field("root", &self.root)
+         .field("count", &self.count)
+         .finish()
+    }
+}
+
+impl<T> RBTree<T>
+where T: Ord
+{
+    pub fn new() -> Self {
+        Self::with_comparator(Natural)
+    }
+
+    // builds a balanced tree in O(n) from keys in ascending order; repeated
+    // keys are kept once
+    pub fn from_sorted_vec(keys: Vec<T>) -> Self {
+        Self::from_sorted_vec_with(keys, Natural)
+    }
+
+    pub fn from_sorted_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from_sorted_vec(iter.into_iter().collect())
+    }
+
+    // empty tree whose keys cache subtree data kept current by `refresh`
+    pub(crate) fn with_refresh(refresh: Refresh<T>) -> Self {
+        let mut tree = Self::new();
+        tree.refresh = Some(refresh);
+        tree
+    }
+}
+
+impl<T, C> RBTree<T, C>
+{
+    // empty tree ordered by `comparator`, e.g. `|a: &T, b: &T| b.cmp(a)` for
+    // descending order
+    pub fn with_comparator(comparator: C) -> Self {
+        RBTree {
+            root: None,
+            count: 0,
+            delete_trace: None,
+            refresh: None,
+            comparator,
+        }
+    }
+
+    pub(crate) fn root(&self) -> &Tree<T> {
+        &self.root
+    }
+
+    // recomputes the size of `node`, and the data its key caches if the tree
+    // has a refresh hook, from its children
+    fn update_node(&self, node: &TreeNode<T>) {
+        update_size(node);
+        if let Some(refresh) = self.refresh {
+            let mut node_ref = node.borrow_mut();
+            let Node { key, left, right, .. } = &mut *node_ref;
+            let left = left.as_ref().map(|left| left.borrow());
+            let right = right.as_ref().map(|right| right.borrow());
+            refresh(key, left.as_ref().map(|left| &left.key), right.as_ref().map(|right| &right.key));
+        }
+    }
+
+    // builds a subtree of `count` nodes taking keys from `keys` in order, so
+    // both sides differ in size by at most one and every leaf sits on one of
+    // the two deepest levels
+    fn build_balanced<I: Iterator<Item = T>>(&self, count: usize, depth: usize, max_depth: usize, keys: &mut I) -> Tree<T> {
+        if count == 0 {
+            return None;
+        }
+        let left_count = (count - 1) / 2;
+        let left = self.build_balanced(left_count, depth + 1, max_depth, keys);
+        let node = Node::new(keys.next().expect("fewer keys than count")).unwrap();
+        let right = self.build_balanced(count - 1 - left_count, depth + 1, max_depth, keys);
+        for child in [&left, &right].into_iter().flatten() {
+            child.borrow_mut().parent = Some(Rc::downgrade(&node));
+        }
+        {
+            let mut node_ref = node.borrow_mut();
+            node_ref.left = left;
+            node_ref.right = right;
+            node_ref.color = if depth == max_depth && depth > 0 {
+                NodeColor::Red
+            } else {
+                NodeColor::Black
+            };
+        }
+        self.update_node(&node);
+        Some(node)
+    }
+
+    // replaces the contents with `keys`, already sorted and distinct, as a
+    // balanced tree built in O(n). All nodes are black except a partially
+    // filled bottom level, which is red so that every path sees the same
+    // number of black nodes.
+    fn rebuild(&mut self, keys: Vec<T>) {
+        let count = keys.len();
+        let max_depth = if count == 0 { 0 } else { count.ilog2() as usize };
+        self.root = self.build_balanced(count, 0, max_depth, &mut keys.into_iter());
+        self.count = count as u32;
+    }
+
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    // installs (or with None removes) the hook that `delete` reports its
+    // steps to; without one deletes do no extra work
+    pub fn set_delete_trace(&mut self, hook: Option<DeleteTrace<T, C>>) {
+        self.delete_trace = hook;
+    }
+
+    fn trace_delete(&self, step: &'static str) {
+        debug_println!("delete: {}", step);
+        if let Some(hook) = self.delete_trace {
+            hook(self, step);
+        }
+    }
+
+    // check if tree is empty
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    fn insert_fix(&mut self, tree_node: TreeNode<T>) -> Tree<T> {
+        let mut is_root = tree_node.borrow().parent.is_none(); 
+        let root = if is_root {
+            Some(tree_node)
+        } else {
+            let mut node = tree_node.clone();
+            let mut parent_color = tree_node.borrow().parent().unwrap().borrow().color;
+            
+            while !is_root && parent_color == NodeColor::Red {
+                let uncle_return = match node.borrow().parent() {
+                    Some(parent) => {
+                        match parent.borrow().parent() {
+                            Some(grandparent) => {
+                                // decided by shape rather than by key so the
+                                // fix-up never consults the comparator
+                                let parent_is_right = !Self::is_left_child(&grandparent, &Some(parent.clone()));
+                                let grandparent = grandparent.borrow();
+                                if parent_is_right {
+                                    Some((grandparent.left.clone(), Direction::Left))
+                                } else {
+                                    Some((grandparent.right.clone(), Direction::Right))
+                                }
+                            },
+                            None => {None}
+                        }
+                    },
+                    None => { None }
+                };
+                
+                match uncle_return {
+                    Some(uncle) => {
+                        let uncle_node = uncle.0;
+                        let side = uncle.1;
+
+                        match side {
+                            Direction::Right => {
+                                let mut parent = node.borrow().parent().unwrap();
+                                if !Self::is_black(&uncle_node) {
+                                    parent.borrow_mut().color = NodeColor::Black;
+                                    uncle_node.unwrap().borrow_mut().color = NodeColor::Black;
+                                    parent.borrow().parent().unwrap().borrow_mut().color = NodeColor::Red;
+                                    node = parent.borrow().parent().unwrap();
+                                } else {
+                                    if !Self::is_left_child(&parent, &Some(node.clone())) {
+                                        let parent_tmp = node.borrow().parent().unwrap();
+                                        node = parent_tmp;
+                                        self.rotate_left(node.clone());
+                                        parent = node.borrow().parent().unwrap();
+                                    } 
+
+                                    parent.borrow_mut().color = NodeColor::Black;
+                                    parent.borrow().parent().unwrap().borrow_mut().color = NodeColor::Red;
+                                    let grandparent = node.borrow().parent().unwrap().borrow().parent().unwrap();
+                                    self.rotate_right(grandparent);
+                                }
+                            },
+                            Direction::Left => {
+                                let mut parent = node.borrow().parent().unwrap();
+                                if !Self::is_black(&uncle_node) {
+                                    parent.borrow_mut().color = NodeColor::Black;
+                                    uncle_node.unwrap().borrow_mut().color = NodeColor::Black;
+                                    parent.borrow().parent().unwrap().borrow_mut().color = NodeColor::Red;
+                                    node = parent.borrow().parent().unwrap();
+                                } else {
+                                    if Self::is_left_child(&parent, &Some(node.clone())) {
+                                        let parent_tmp = node.borrow().parent().unwrap();
+                                        node = parent_tmp;
+                                        self.rotate_right(node.clone());
+                                        parent = node.borrow().parent().unwrap();
+                                    }
+                                    parent.borrow_mut().color = NodeColor::Black;
+                                    parent.borrow().parent().unwrap().borrow_mut().color = NodeColor::Red;
+                                    let grandparent = node.borrow().parent().unwrap().borrow().parent().unwrap();
+                                    self.rotate_left(grandparent);
+                                }
+                            }
+                        }
+                    },
+                    None => {
+                        break;
+                    }
+                }
+                is_root = node.borrow().parent.is_none();
+                if !is_root {
+                    parent_color = node.borrow().parent().unwrap().borrow().color;
+                }
+            } 
+
+            while node.borrow().parent.is_some() {
+                let p = node.borrow().parent().unwrap();
+                node = p;
+            }
+            Some(node)
+        };
+        root.as_ref().unwrap().borrow_mut().color = NodeColor::Black;
+        root
+    }
+
+    fn rotate_left(&mut self, tree_node: TreeNode<T>) {
+        let cur_parent = tree_node;
+        let right_child = cur_parent.borrow().right.clone();
+    
+        cur_parent.borrow_mut().right = right_child.as_ref().and_then(|n| n.borrow().left.clone());
+    
+        if let Some(ref right_child) = right_child {
+            if let Some(ref right_child_left) = right_child.borrow().left {
+                right_child_left.borrow_mut().parent = Some(Rc::downgrade(&cur_parent));
+            }
+        }
+    
+        if let Some(ref right_child) = right_child {
+            right_child.borrow_mut().parent = cur_parent.borrow().parent.clone();
+        }
+    
+        match cur_parent.borrow().parent() {
+            Some(ref grandparent) => {
+                let left_child_ptr = grandparent.borrow().left.as_ref().map(|n| n.as_ptr()).unwrap_or(std::ptr::null_mut());
+                let cur_parent_ptr = cur_parent.as_ptr();
+                
+                if std::ptr::eq(left_child_ptr, cur_parent_ptr) {
+                    grandparent.borrow_mut().left = right_child.clone();
+                } else {
+                    grandparent.borrow_mut().right = right_child.clone();
+                }
+            },
+            None => {
+                self.root = right_child.clone();
+            },
+        }
+    
+        if let Some(ref right_child) = right_child {
+            right_child.borrow_mut().left = Some(cur_parent.clone());
+            self.update_node(&cur_parent);
+            self.update_node(right_child);
+        }
+        cur_parent.borrow_mut().parent = right_child.as_ref().map(Rc::downgrade);
+    }
+
+    fn rotate_right(&mut self, tree_node: TreeNode<T>) {
+        let cur_parent = tree_node;
+        let left_child = cur_parent.borrow().left.clone();
+
+        cur_parent.borrow_mut().left = left_child.as_ref().and_then(|n| n.borrow().right.clone());
+
+        if let Some(ref left_child) = left_child {
+            if let Some(ref left_child_right) = left_child.borrow().right {
+                left_child_right.borrow_mut().parent = Some(Rc::downgrade(&cur_parent));
+            }
+        }
+
+        if let Some(ref left_child) = left_child {
+            left_child.borrow_mut().parent = cur_parent.borrow().parent.clone();
+        }
+
+        match cur_parent.borrow().parent() {
+            Some(ref grandparent) => {
+                let right_child_ptr = grandparent.borrow().right.as_ref().map(|n| n.as_ptr()).unwrap_or(std::ptr::null_mut());
+                let cur_parent_ptr = cur_parent.as_ptr();
+
+                if std::ptr::eq(right_child_ptr, cur_parent_ptr) {
+                    grandparent.borrow_mut().right = left_child.clone();
+                } else {
+                    grandparent.borrow_mut().left = left_child.clone();
+                }
+            },
+            None => {
+                self.root = left_child.clone();
+            },
+        }
+
+        if let Some(ref left_child) = left_child {
+            left_child.borrow_mut().right = Some(cur_parent.clone());
+            self.update_node(&cur_parent);
+            self.update_node(left_child);
+        }
+        cur_parent.borrow_mut().parent = left_child.as_ref().map(Rc::downgrade);
+    }
+
+    // unlinks `u` from the tree and rebalances
+    fn delete_node(&mut self, u: TreeNode<T>) {
+        let v = u.borrow().left.clone();
+        let w = u.borrow().right.clone();
+
+        let mut u_original_color = u.borrow().color;
+        let x: Tree<T>;
+        // x may be None, so its parent has to be tracked separately
+        let x_parent: Tree<T>;
+
+        if v.is_none() {
+            x = w.clone();
+            x_parent = u.borrow().parent();
+            self.transplant(&u, w);
+        } else if w.is_none() {
+            x = v.clone();
+            x_parent = u.borrow().parent();
+            self.transplant(&u, v);
+        } else {
+            let y = self.find_min(w).unwrap();
+            u_original_color = y.borrow().color;
+            x = y.borrow().right.clone();
+            if Rc::ptr_eq(&y.borrow().parent().unwrap(), &u) {
+                x_parent = Some(y.clone());
+            } else {
+                x_parent = y.borrow().parent();
+                self.transplant(&y, x.clone());
+                let u_right = u.borrow().right.clone();
+                if let Some(ref u_right) = u_right {
+                    u_right.borrow_mut().parent = Some(Rc::downgrade(&y));
+                }
+                y.borrow_mut().right = u_right;
+            }
+            self.transplant(&u, Some(y.clone()));
+            if let Some(ref v) = v {
+                v.borrow_mut().parent = Some(Rc::downgrade(&y));
+            }
+            y.borrow_mut().left = v;
+            y.borrow_mut().color = u.borrow().color;
+        }
+
+        // every subtree size changes on the path from the removed position up
+        let mut ancestor = x_parent.clone();
+        while let Some(node) = ancestor {
+            self.update_node(&node);
+            ancestor = node.borrow().parent();
+        }
+        self.count -= 1;
+        self.trace_delete("unlinked");
+
+        if u_original_color == NodeColor::Black {
+            self.delete_fix(x, x_parent);
+        }
+
+        let mut u_ref = u.borrow_mut();
+        u_ref.parent = None;
+        u_ref.left = None;
+        u_ref.right = None;
+        u_ref.size = 1;
+    }
+
+    fn delete_fix(&mut self, x: Tree<T>, p: Tree<T>) {
+        let mut cur_x = x;
+        let mut cur_p = p;
+        while Self::is_black(&cur_x) {
+            let parent = match cur_p {
+                Some(ref parent) => parent.clone(),
+                None => break,
+            };
+            // the side the sibling of x is on
+            let side = if Self::is_left_child(&parent, &cur_x) {
+                Direction::Right
+            } else {
+                Direction::Left
+            };
+            match side {
+                Direction::Right => {
+                    let mut s = parent.borrow().right.clone().unwrap();
+                    if s.borrow().color == NodeColor::Red {
+                        s.borrow_mut().color = NodeColor::Black;
+                        parent.borrow_mut().color = NodeColor::Red;
+                        self.rotate_left(parent.clone());
+                        s = parent.borrow().right.clone().unwrap();
+                        self.trace_delete("red sibling rotated");
+                    }
+                    let s_left = s.borrow().left.clone();
+                    let s_right = s.borrow().right.clone();
+
+                    if Self::is_black(&s_left) && Self::is_black(&s_right) {
+                        s.borrow_mut().color = NodeColor::Red;
+                        cur_p = parent.borrow().parent();
+                        cur_x = Some(parent);
+                        self.trace_delete("sibling recolored");
+                    } else {
+                        if Self::is_black(&s_right) {
+                            s_left.as_ref().unwrap().borrow_mut().color = NodeColor::Black;
+                            s.borrow_mut().color = NodeColor::Red;
+                            self.rotate_right(s);
+                            s = parent.borrow().right.clone().unwrap();
+                            self.trace_delete("sibling rotated");
+                        }
+                        s.borrow_mut().color = parent.borrow().color;
+                        parent.borrow_mut().color = NodeColor::Black;
+                        if let Some(ref s_right) = s.borrow().right {
+                            s_right.borrow_mut().color = NodeColor::Black;
+                        }
+                        self.rotate_left(parent);
+                        cur_x = self.root.clone();
+                        cur_p = None;
+                        self.trace_delete("parent rotated");
+                    }
+                },
+                Direction::Left => {
+                    let mut s = parent.borrow().left.clone().unwrap();
+                    if s.borrow().color == NodeColor::Red {
+                        s.borrow_mut().color = NodeColor::Black;
+                        parent.borrow_mut().color = NodeColor::Red;
+                        self.rotate_right(parent.clone());
+                        s = parent.borrow().left.clone().unwrap();
+                        self.trace_delete("red sibling rotated");
+                    }
+                    let s_left = s.borrow().left.clone();
+                    let s_right = s.borrow().right.clone();
+
+                    if Self::is_black(&s_left) && Self::is_black(&s_right) {
+                        s.borrow_mut().color = NodeColor::Red;
+                        cur_p = parent.borrow().parent();
+                        cur_x = Some(parent);
+                        self.trace_delete("sibling recolored");
+                    } else {
+                        if Self::is_black(&s_left) {
+                            s_right.as_ref().unwrap().borrow_mut().color = NodeColor::Black;
+                            s.borrow_mut().color = NodeColor::Red;
+                            self.rotate_left(s);
+                            s = parent.borrow().left.clone().unwrap();
+                            self.trace_delete("sibling rotated");
+                        }
+                        s.borrow_mut().color = parent.borrow().color;
+                        parent.borrow_mut().color = NodeColor::Black;
+                        if let Some(ref s_left) = s.borrow().left {
+                            s_left.borrow_mut().color = NodeColor::Black;
+                        }
+                        self.rotate_right(parent);
+                        cur_x = self.root.clone();
+                        cur_p = None;
+                        self.trace_delete("parent rotated");
+                    }
+                }
+            }
+        }
+        if let Some(ref x) = cur_x {
+            x.borrow_mut().color = NodeColor::Black;
+        }
+    }
+
+    // nil leaves count as black
+    fn is_black(node: &Tree<T>) -> bool {
+        node.as_ref().is_none_or(|n| n.borrow().color == NodeColor::Black)
+    }
+
+    fn is_left_child(parent: &TreeNode<T>, node: &Tree<T>) -> bool {
+        match (&parent.borrow().left, node) {
+            (Some(left), Some(node)) => Rc::ptr_eq(left, node),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+
+    fn transplant(&mut self, u: &TreeNode<T>, v: Tree<T>) {
+        let u_p = u.borrow().parent();
+        match u_p {
+            None => {
+                self.root = v.clone();
+            },
+            Some(ref u_p) => {
+                let is_left = u_p.borrow().left.as_ref().is_some_and(|l| Rc::ptr_eq(l, u));
+                if is_left {
+                    u_p.borrow_mut().left = v.clone();
+                } else {
+                    u_p.borrow_mut().right = v.clone();
+                }
+            },
+        }
+        if let Some(ref v) = v {
+            v.borrow_mut().parent = u_p.as_ref().map(Rc::downgrade);
+        }
+    }
+
+    // number of black nodes on any path from `node` down to a leaf
+    fn black_height(node: &Tree<T>) -> usize {
+        let mut height = 0;
+        let mut current = node.clone();
+        while let Some(n) = current {
+            if n.borrow().color == NodeColor::Black {
+                height += 1;
+            }
+            current = n.borrow().left.clone();
+        }
+        height
+    }
+
+    // joins two subtrees and a middle node into self.root, where every key in
+    // `left` is less than the middle one and every key in `right` greater.
+    // Takes the black heights of both sides and returns the one of the
+    // result; costs O(|left_bh - right_bh| + 1).
+    fn join_at(&mut self, left: Tree<T>, mut left_bh: usize, mid: TreeNode<T>, right: Tree<T>, mut right_bh: usize) -> usize {
+        // a red root can always be made black, so both sides start black-rooted
+        for (tree, bh) in [(&left, &mut left_bh), (&right, &mut right_bh)] {
+            if let Some(root) = tree {
+                let mut root = root.borrow_mut();
+                root.parent = None;
+                if root.color == NodeColor::Red {
+                    root.color = NodeColor::Black;
+                    *bh += 1;
+                }
+            }
+        }
+        {
+            let mut mid_ref = mid.borrow_mut();
+            mid_ref.color = NodeColor::Red;
+            mid_ref.parent = None;
+            mid_ref.left = None;
+            mid_ref.right = None;
+        }
+
+        if left_bh == right_bh {
+            for child in [&left, &right].into_iter().flatten() {
+                child.borrow_mut().parent = Some(Rc::downgrade(&mid));
+            }
+            mid.borrow_mut().left = left;
+            mid.borrow_mut().right = right;
+            mid.borrow_mut().color = NodeColor::Black;
+            self.update_node(&mid);
+            self.root = Some(mid);
+            return left_bh + 1;
+        }
+
+        let tall_is_left = left_bh > right_bh;
+        let (tall, short, target_bh) = if tall_is_left {
+            (left, right, right_bh)
+        } else {
+            (right, left, left_bh)
+        };
+        let mut bh = std::cmp::max(left_bh, right_bh);
+        self.root = tall.clone();
+
+        // walk the inner spine of the taller tree down to a black subtree of
+        // the same black height as the shorter one
+        let mut cur = tall;
+        let mut parent: Tree<T> = None;
+        while !(Self::is_black(&cur) && bh == target_bh) {
+            let node = cur.unwrap();
+            if node.borrow().color == NodeColor::Black {
+                bh -= 1;
+            }
+            cur = if tall_is_left {
+                node.borrow().right.clone()
+            } else {
+                node.borrow().left.clone()
+            };
+            parent = Some(node);
+        }
+        // the taller root is black and too high, so at least one step was taken
+        let parent = parent.unwrap();
+
+        // the red middle node takes the place of that subtree
+        let (mid_left, mid_right) = if tall_is_left { (cur, short) } else { (short, cur) };
+        for child in [&mid_left, &mid_right].into_iter().flatten() {
+            child.borrow_mut().parent = Some(Rc::downgrade(&mid));
+        }
+        mid.borrow_mut().left = mid_left;
+        mid.borrow_mut().right = mid_right;
+        mid.borrow_mut().parent = Some(Rc::downgrade(&parent));
+        self.update_node(&mid);
+        if tall_is_left {
+            parent.borrow_mut().right = Some(mid.clone());
+        } else {
+            parent.borrow_mut().left = Some(mid.clone());
+        }
+
+        let mut ancestor = Some(parent.clone());
+        while let Some(node) = ancestor {
+            self.update_node(&node);
+            ancestor = node.borrow().parent();
+        }
+
+        // going back up, a black node whose inner child and grandchild are
+        // both red is rotated, which pushes the red pair one level up
+        let mut ancestor = Some(parent);
+        while let Some(node) = ancestor {
+            ancestor = node.borrow().parent();
+            if node.borrow().color == NodeColor::Red {
+                continue;
+            }
+            let child = if tall_is_left {
+                node.borrow().right.clone()
+            } else {
+                node.borrow().left.clone()
+            };
+            let grandchild = child.as_ref().and_then(|c| {
+                if tall_is_left { c.borrow().right.clone() } else { c.borrow().left.clone() }
+            });
+            if !Self::is_black(&child) && !Self::is_black(&grandchild) {
+                grandchild.unwrap().borrow_mut().color = NodeColor::Black;
+                if tall_is_left {
+                    self.rotate_left(node);
+                } else {
+                    self.rotate_right(node);
+                }
+            }
+        }
+
+        let root = self.root.clone().unwrap();
+        let max_bh = std::cmp::max(left_bh, right_bh);
+        if root.borrow().color == NodeColor::Red {
+            root.borrow_mut().color = NodeColor::Black;
+            return max_bh + 1;
+        }
+        max_bh
+    }
+
+    fn join_trees(&self, left: Tree<T>, left_bh: usize, mid: TreeNode<T>, right: Tree<T>, right_bh: usize) -> (Tree<T>, usize) {
+        // joining is purely structural, so the temporary needs no comparator
+        let mut tree = RBTree::<T, ()>::with_comparator(());
+        tree.refresh = self.refresh;
+        let bh = tree.join_at(left, left_bh, mid, right, right_bh);
+        (tree.root, bh)
+    }
+
+    fn find_min(&self, tree: Tree<T>) -> Tree<T> {
+        match tree {
+            Some(sub_tree) => {
+                let mut left = Some(sub_tree.clone());
+                while left.as_ref().unwrap().borrow().left.clone().is_some() {
+                    left = left.unwrap().borrow().left.clone();
+                }
+                left
+            },
+            None => {
+                tree
+            }
+        }
+    }
+
+    fn find_max(&self, tree: Tree<T>) -> Tree<T> {
+        match tree {
+            Some(sub_tree) => {
+                let mut right = Some(sub_tree.clone());
+                while right.as_ref().unwrap().borrow().right.clone().is_some() {
+                    right = right.unwrap().borrow().right.clone();
+                }
+                right
+            },
+            None => {
+                tree
+            }
+        }
+    }
+
+    // in-order neighbour of `node` through the parent links: down one side
+    // and then as far as possible the other way, or else up to the first
+    // ancestor reached from the near side
+    fn neighbour(node: &TreeNode<T>, forward: bool) -> Tree<T> {
+        let down = |node: &TreeNode<T>, left: bool| {
+            let node = node.borrow();
+            if left { node.left.clone() } else { node.right.clone() }
+        };
+        if let Some(mut next) = down(node, !forward) {
+            while let Some(below) = down(&next, forward) {
+                next = below;
+            }
+            return Some(next);
+        }
+        let mut child = node.clone();
+        loop {
+            let parent = child.borrow().parent()?;
+            if Self::is_left_child(&parent, &Some(child.clone())) == forward {
+                return Some(parent);
+            }
+            child = parent;
+        }
+    }
+
+    // the node after `current` in the given direction, where no node stands
+    // for the ghost position past both ends
+    fn step(&self, current: &Tree<T>, forward: bool) -> Tree<T> {
+        match current {
+            Some(node) => Self::neighbour(node, forward),
+            None if forward => self.find_min(self.root.clone()),
+            None => self.find_max(self.root.clone()),
+        }
+    }
+
+    pub fn cursor_front(&self) -> Cursor<'_, T, C> {
+        Cursor { current: self.find_min(self.root.clone()), tree: self }
+    }
+
+    pub fn cursor_back(&self) -> Cursor<'_, T, C> {
+        Cursor { current: self.find_max(self.root.clone()), tree: self }
+    }
+
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T, C> {
+        CursorMut { current: self.find_min(self.root.clone()), tree: self }
+    }
+
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T, C> {
+        CursorMut { current: self.find_max(self.root.clone()), tree: self }
+    }
+
+    // count the number of leaves in a tree
+    pub fn leaves(&self) -> u32 {
+        if self.root.is_none() {
+            return 0;
+        }
+        let root = self.root.as_ref().unwrap().clone();
+        let mut stack: Vec<Tree<T>> = Vec::new();
+        stack.push(Some(root));
+
+        let mut count = 0;
+        while !stack.is_empty() {
+            let node = stack.pop();
+            let mut node_left = None;
+            let mut node_right = None;
+
+            if let Some(Some(ref node)) = node {
+                node_left = node.borrow().left.clone();
+                node_right = node.borrow().right.clone();
+            }
+
+            if node_left.is_some() {
+                stack.push(node_left.clone());
+            }
+
+            if node_right.is_some() {
+                stack.push(node_right.clone());
+            }
+
+            if node_left.is_none() && node_right.is_none() {
+                count += 1;
+            }
+        }
+        count
+    }
+
+    // 4- return the height of a tree
+    pub fn height(&self) -> u32 {
+        if self.root.is_none() {
+            return 0;
+        }
+        let root = self.root.as_ref().unwrap().clone();
+        let mut queue: VecDeque<Tree<T>> = VecDeque::new();
+        queue.push_back(Some(root));
+
+        let mut height = 0;
+        while !queue.is_empty() {
+            let n = queue.len();
+            for _ in 0..n {
+                let node = queue.pop_front().unwrap().unwrap();
+                for child in [node.borrow().left.clone(), node.borrow().right.clone()] {
+                    if child.is_some() {
+                        queue.push_back(child);
+                    }
+                }
+            }
+            height += 1;
+        }
+        height
+    }
+
+    pub fn min(&self) -> Tree<T> {
+        self.find_min(self.root.clone())
+    }
+
+    pub fn max(&self) -> Tree<T> {
+        self.find_max(self.root.clone())
+    }
+
+    fn levelorder_nodes(&self) -> VecDeque<Tree<T>> {
+        let root = self.root.as_ref().unwrap().clone();
+        let mut queue: VecDeque<Tree<T>> = VecDeque::new();
+        queue.push_back(Some(root));
+        let mut res: VecDeque<Tree<T>> = VecDeque::new();
+        while !queue.is_empty() {
+            let n = queue.len();
+            for _ in 0..n {
+                let node = queue.pop_front().unwrap().unwrap();
+                res.push_back(Some(node.clone()));
+                for child in [node.borrow().left.clone(), node.borrow().right.clone()] {
+                    if child.is_some() {
+                        queue.push_back(child);
+                    }
+                }
+            }
+        }
+        res
+    }
+}
+
+impl<T, C> RBTree<T, C>
+where C: Comparator<T>
+{
+    // builds a balanced tree in O(n) from keys already in `comparator` order;
+    // repeated keys are kept once
+    pub fn from_sorted_vec_with(keys: Vec<T>, comparator: C) -> Self {
+        Self::build_sorted(keys, comparator, None)
+    }
+
+    fn build_sorted(mut keys: Vec<T>, comparator: C, refresh: Option<Refresh<T>>) -> Self {
+        keys.dedup_by(|a, b| comparator.compare(a, b) == Ordering::Equal);
+        debug_assert!(keys.windows(2).all(|w| comparator.compare(&w[0], &w[1]) == Ordering::Less), "from_sorted_vec requires sorted input");
+        let mut tree = Self::with_comparator(comparator);
+        tree.refresh = refresh;
+        tree.rebuild(keys);
+        tree
+    }
+
+    // compares a stored key with a borrowed probe, like the std collections do
+    fn cmp_key<Q>(&self, key: &T, probe: &Q) -> Ordering
+    where T: std::borrow::Borrow<Q>, C: Comparator<Q>, Q: ?Sized
+    {
+        self.comparator.compare(<T as std::borrow::Borrow<Q>>::borrow(key), probe)
+    }
+
+    // insert a node to the red-black tree, returning false if the key was
+    // already present
+    pub fn insert(&mut self, key: T) -> bool {
+        match self.probe(&key) {
+            Probe::Found(_) => false,
+            Probe::Vacant { parent, go_left } => {
+                self.insert_vacant(parent, go_left, Node::new(key).unwrap());
+                true
+            }
+        }
+    }
+
+    // looks `key` up in a single descent, reporting where it would be linked
+    // if it is missing
+    pub(crate) fn probe<Q>(&self, key: &Q) -> Probe<T>
+    where T: std::borrow::Borrow<Q>, C: Comparator<Q>, Q: ?Sized
+    {
+        let mut parent = None;
+        let mut go_left = false;
+        let mut current = self.root.clone();
+        while let Some(node) = current {
+            let order = self.cmp_key(&node.borrow().key, key);
+            current = match order {
+                Ordering::Equal => return Probe::Found(node),
+                Ordering::Greater => {
+                    go_left = true;
+                    node.borrow().left.clone()
+                },
+                Ordering::Less => {
+                    go_left = false;
+                    node.borrow().right.clone()
+                },
+            };
+            parent = Some(node);
+        }
+        Probe::Vacant { parent, go_left }
+    }
+
+    // links a fresh red node at the position a `Probe::Vacant` reported for
+    // its key and rebalances
+    pub(crate) fn insert_vacant(&mut self, parent: Tree<T>, go_left: bool, new_node: TreeNode<T>) {
+        self.count += 1;
+        self.update_node(&new_node);
+        match parent {
+            Some(ref parent) => {
+                new_node.borrow_mut().parent = Some(Rc::downgrade(parent));
+                if go_left {
+                    parent.borrow_mut().left = Some(new_node.clone());
+                } else {
+                    parent.borrow_mut().right = Some(new_node.clone());
+                }
+            },
+            None => self.root = Some(new_node.clone()),
+        }
+        let mut ancestor = parent;
+        while let Some(node) = ancestor {
+            self.update_node(&node);
+            ancestor = node.borrow().parent();
+        }
+        self.root = self.insert_fix(new_node);
+    }
+
+    // cursor on `key`, or on the next larger key if it is missing
+    pub fn cursor_at<Q>(&self, key: &Q) -> Cursor<'_, T, C>
+    where T: std::borrow::Borrow<Q>, C: Comparator<Q>, Q: ?Sized
+    {
+        Cursor { current: self.seek(key), tree: self }
+    }
+
+    pub fn cursor_at_mut<Q>(&mut self, key: &Q) -> CursorMut<'_, T, C>
+    where T: std::borrow::Borrow<Q>, C: Comparator<Q>, Q: ?Sized
+    {
+        CursorMut { current: self.seek(key), tree: self }
+    }
+
+    // the node holding the smallest key not less than `key`
+    fn seek<Q>(&self, key: &Q) -> Tree<T>
+    where T: std::borrow::Borrow<Q>, C: Comparator<Q>, Q: ?Sized
+    {
+        let mut best = None;
+        let mut current = self.root.clone();
+        while let Some(node) = current {
+            let order = self.cmp_key(&node.borrow().key, key);
+            current = match order {
+                Ordering::Equal => return Some(node),
+                Ordering::Greater => node.borrow().left.clone(),
+                Ordering::Less => node.borrow().right.clone(),
+            };
+            if order == Ordering::Greater {
+                best = Some(node);
+            }
+        }
+        best
+    }
+
+    // returns the node holding `key`, if any
+    pub fn search<Q>(&self, key: &Q) -> Tree<T>
+    where T: std::borrow::Borrow<Q>, C: Comparator<Q>, Q: ?Sized
+    {
+        let mut current = self.root.clone();
+        while let Some(node) = current {
+            current = match self.cmp_key(&node.borrow().key, key) {
+                Ordering::Equal => return Some(node.clone()),
+                Ordering::Greater => node.borrow().left.clone(),
+                Ordering::Less => node.borrow().right.clone(),
+            };
+        }
+        None
+    }
+
+    pub fn search_element<Q>(&self, key: &Q) -> bool
+    where T: std::borrow::Borrow<Q>, C: Comparator<Q>, Q: ?Sized
+    {
+        self.search(key).is_some()
+    }
+
+    // splits the subtree rooted at `node`, of black height `bh`, into the keys
+    // less than and greater than `key` along with their black heights, handing
+    // back the node holding `key` if there is one
+    #[allow(clippy::type_complexity)]
+    fn split_rec<Q>(&self, node: Tree<T>, bh: usize, key: &Q) -> ((Tree<T>, usize), Tree<T>, (Tree<T>, usize))
+    where T: std::borrow::Borrow<Q>, C: Comparator<Q>, Q: ?Sized
+    {
+        let node = match node {
+            Some(node) => node,
+            None => return ((None, 0), None, (None, 0)),
+        };
+        let child_bh = if node.borrow().color == NodeColor::Black { bh - 1 } else { bh };
+        let left = node.borrow_mut().left.take();
+        let right = node.borrow_mut().right.take();
+        for child in [&left, &right].into_iter().flatten() {
+            child.borrow_mut().parent = None;
+        }
+
+        let order = self.cmp_key(&node.borrow().key, key);
+        match order {
+            Ordering::Greater => {
+                let (less, found, (greater, greater_bh)) = self.split_rec(left, child_bh, key);
+                (less, found, self.join_trees(greater, greater_bh, node, right, child_bh))
+            },
+            Ordering::Less => {
+                let ((less, less_bh), found, greater) = self.split_rec(right, child_bh, key);
+                (self.join_trees(left, child_bh, node, less, less_bh), found, greater)
+            },
+            Ordering::Equal => ((left, child_bh), Some(node), (right, child_bh)),
+        }
+    }
+
+    // splits the tree in two at `key`: self keeps the keys less than it and
+    // the returned tree gets the rest, in O(log n)
+    pub fn split_off<Q>(&mut self, key: &Q) -> Self
+    where T: std::borrow::Borrow<Q>, C: Comparator<Q>+Clone, Q: ?Sized
+    {
+        let bh = Self::black_height(&self.root);
+        let root = self.root.take();
+        let ((less, _), found, (greater, greater_bh)) = self.split_rec(root, bh, key);
+        let greater = match found {
+            Some(node) => self.join_trees(None, 0, node, greater, greater_bh).0,
+            None => greater,
+        };
+
+        self.root = less;
+        let mut other = RBTree {
+            root: greater,
+            count: 0,
+            delete_trace: self.delete_trace,
+            refresh: self.refresh,
+            comparator: self.comparator.clone(),
+        };
+        for tree in [&mut *self, &mut other] {
+            if let Some(ref root) = tree.root {
+                root.borrow_mut().color = NodeColor::Black;
+            }
+            tree.count = size(&tree.root) as u32;
+        }
+        other
+    }
+
+    // moves every key of `other` into self, leaving `other` empty. When all
+    // keys of one tree are less than those of the other the two are joined
+    // in O(log n), otherwise the keys are inserted one by one.
+    pub fn append(&mut self, other: &mut Self) {
+        if other.root.is_none() {
+            return;
+        }
+        if self.root.is_none() {
+            // only the keys move across, self keeps its own hooks
+            self.root = other.root.take();
+            self.count = std::mem::take(&mut other.count);
+            return;
+        }
+
+        let (self_min, self_max) = (self.min().unwrap(), self.max().unwrap());
+        let (other_min, other_max) = (other.min().unwrap(), other.max().unwrap());
+
+        if self.comparator.compare(&self_max.borrow().key, &other_min.borrow().key) == Ordering::Less {
+            // the smallest node of the right tree becomes the joining node
+            other.delete_node(other_min.clone());
+            let (left_bh, right_bh) = (Self::black_height(&self.root), Self::black_height(&other.root));
+            let (left, right) = (self.root.take(), other.root.take());
+            self.join_at(left, left_bh, other_min, right, right_bh);
+        } else if self.comparator.compare(&other_max.borrow().key, &self_min.borrow().key) == Ordering::Less {
+            self.delete_node(self_min.clone());
+            let (left_bh, right_bh) = (Self::black_height(&other.root), Self::black_height(&self.root));
+            let (left, right) = (other.root.take(), self.root.take());
+            self.join_at(left, left_bh, self_min, right, right_bh);
+        } else {
+            // the nodes of `other` are unlinked and reinserted as they are,
+            // so no key has to be cloned
+            let mut stack: Vec<TreeNode<T>> = other.root.take().into_iter().collect();
+            while let Some(node) = stack.pop() {
+                {
+                    let mut node_ref = node.borrow_mut();
+                    stack.extend(node_ref.left.take());
+                    stack.extend(node_ref.right.take());
+                    node_ref.parent = None;
+                    node_ref.color = NodeColor::Red;
+                    node_ref.size = 1;
+                }
+                let probe = self.probe(&node.borrow().key);
+                if let Probe::Vacant { parent, go_left } = probe {
+                    self.insert_vacant(parent, go_left, node);
+                }
+            }
+        }
+        self.count = size(&self.root) as u32;
+        other.count = 0;
+    }
+
+    // number of keys within `range`, in O(log n) using subtree sizes
+    pub fn count_range<Q, R>(&self, range: R) -> usize
+    where T: std::borrow::Borrow<Q>, C: Comparator<Q>, Q: ?Sized, R: RangeBounds<Q>
+    {
+        let upper = match range.end_bound() {
+            Bound::Included(upper) => self.count_below(upper, true),
+            Bound::Excluded(upper) => self.count_below(upper, false),
+            Bound::Unbounded => self.count as usize,
+        };
+        let lower = match range.start_bound() {
+            Bound::Included(lower) => self.count_below(lower, false),
+            Bound::Excluded(lower) => self.count_below(lower, true),
+            Bound::Unbounded => 0,
+        };
+        upper.saturating_sub(lower)
+    }
+
+    // number of keys strictly less than `key`
+    pub fn rank<Q>(&self, key: &Q) -> usize
+    where T: std::borrow::Borrow<Q>, C: Comparator<Q>, Q: ?Sized
+    {
+        self.count_below(key, false)
+    }
+
+    fn count_below<Q>(&self, key: &Q, inclusive: bool) -> usize
+    where T: std::borrow::Borrow<Q>, C: Comparator<Q>, Q: ?Sized
+    {
+        let mut count = 0;
+        let mut current = self.root.clone();
+        while let Some(node) = current {
+            let node_ref = node.borrow();
+            let order = self.cmp_key(&node_ref.key, key);
+            if order == Ordering::Less || (inclusive && order == Ordering::Equal) {
+                count += size(&node_ref.left) + 1;
+                current = node_ref.right.clone();
+            } else {
+                current = node_ref.left.clone();
+            }
+        }
+        count
+    }
+}
+
+impl<T, C> RBTree<T, C>
+where T: Clone, C: Comparator<T>
+{
+    // returns the stored key equal to `key`, if any
+    pub fn get<Q>(&self, key: &Q) -> Option<T>
+    where T: std::borrow::Borrow<Q>, C: Comparator<Q>, Q: ?Sized
+    {
+        self.search(key).map(|node| node.borrow().key.clone())
+    }
+
+    // smallest key
+    pub fn first(&self) -> Option<T> {
+        self.min().map(|node| node.borrow().key.clone())
+    }
+
+    // largest key
+    pub fn last(&self) -> Option<T> {
+        self.max().map(|node| node.borrow().key.clone())
+    }
+
+    // delete a node from the red-black tree, returning its key. A node still
+    // held through a handle from `search` is unlinked all the same and its
+    // key cloned out.
+    pub fn delete<Q>(&mut self, key: &Q) -> Result<T, TreeError>
+    where T: std::borrow::Borrow<Q>, C: Comparator<Q>, Q: ?Sized
+    {
+        let node = self.search(key).ok_or(TreeError::KeyNotFound)?;
+        Ok(self.take_node(node))
+    }
+
+    // removes and returns the smallest key, in a single descent, cloning it
+    // out of a held node like `delete`
+    pub fn pop_first(&mut self) -> Option<T> {
+        let node = self.min()?;
+        Some(self.take_node(node))
+    }
+
+    // removes and returns the largest key
+    pub fn pop_last(&mut self) -> Option<T> {
+        let node = self.max()?;
+        Some(self.take_node(node))
+    }
+
+    // unlinks a node found by `search` or `probe`, returning its key
+    pub(crate) fn take_node(&mut self, node: TreeNode<T>) -> T {
+        self.delete_node(node.clone());
+        take_key(node)
+    }
+
+    // keeps only the keys for which `keep` returns true. The survivors are
+    // rebuilt into a balanced tree in O(n) instead of the others being
+    // deleted one at a time.
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, keep: F) {
+        let mut keys = take_keys(self.root.take(), self.count as usize);
+        keys.retain(keep);
+        self.rebuild(keys);
+    }
+
+    // removes every key, handing them out in order
+    pub fn drain(&mut self) -> IntoIter<T> {
+        let keys = take_keys(self.root.take(), self.count as usize);
+        self.count = 0;
+        IntoIter { keys: keys.into_iter() }
+    }
+
+    // removes the keys within `range`, handing them out in order. The tree
+    // is split at both bounds and the outer pieces joined back together, in
+    // O(log n + k) for k removed keys.
+    pub fn drain_range<R: RangeBounds<T>>(&mut self, range: R) -> IntoIter<T> {
+        let bh = Self::black_height(&self.root);
+        let whole = (self.root.take(), bh);
+        let (below, rest) = match range.start_bound() {
+            Bound::Included(lower) => self.split_at(whole, lower, false),
+            Bound::Excluded(lower) => self.split_at(whole, lower, true),
+            Bound::Unbounded => ((None, 0), whole),
+        };
+        let ((drained, _), above) = match range.end_bound() {
+            Bound::Included(upper) => self.split_at(rest, upper, true),
+            Bound::Excluded(upper) => self.split_at(rest, upper, false),
+            Bound::Unbounded => (rest, (None, 0)),
+        };
+        let drained_count = size(&drained);
+        let keys = take_keys(drained, drained_count);
+
+        self.root = self.concat(below, above);
+        if let Some(ref root) = self.root {
+            root.borrow_mut().color = NodeColor::Black;
+        }
+        self.count = size(&self.root) as u32;
+        IntoIter { keys: keys.into_iter() }
+    }
+
+    // removes the keys within `range`, returning how many there were
+    pub fn remove_range<R: RangeBounds<T>>(&mut self, range: R) -> usize {
+        self.drain_range(range).len()
+    }
+
+    // splits a detached tree and its black height at `key`, the node holding
+    // `key` going to the left piece if `found_left` and to the right one
+    // otherwise
+    #[allow(clippy::type_complexity)]
+    fn split_at(&self, tree: (Tree<T>, usize), key: &T, found_left: bool) -> ((Tree<T>, usize), (Tree<T>, usize)) {
+        let (less, found, greater) = self.split_rec(tree.0, tree.1, key);
+        match found {
+            Some(node) if found_left => (self.join_trees(less.0, less.1, node, None, 0), greater),
+            Some(node) => (less, self.join_trees(None, 0, node, greater.0, greater.1)),
+            None => (less, greater),
+        }
+    }
+
+    // joins two detached trees, every key of `left` less than those of
+    // `right`, through the smallest node of `right`
+    fn concat(&self, left: (Tree<T>, usize), right: (Tree<T>, usize)) -> Tree<T> {
+        let min_key = match self.find_min(right.0.clone()) {
+            Some(min) if left.0.is_some() => min.borrow().key.clone(),
+            _ => return left.0.or(right.0),
+        };
+        let (_, min, (rest, rest_bh)) = self.split_rec(right.0, right.1, &min_key);
+        let min = min.expect("smallest key is in its own tree");
+        self.join_trees(left.0, left.1, min, rest, rest_bh).0
+    }
+
+    pub fn inorder_traversal(&self) -> Vec<T> {
+        let mut result = Vec::new();
+        let mut root = self.root.clone();
+        let mut stack: Vec<TreeNode<T>> = Vec::new();
+        while !stack.is_empty() || root.is_some() {
+            if let Some(node) = root {
+                root = node.borrow().left.clone();
+                stack.push(node);
+            } else {
+                let pop = stack.pop().unwrap();
+                result.push(pop.borrow().key.clone());
+                root = pop.borrow().right.clone();
+            }
+        }
+        result
+    }
+
+    // lazy in-order iterator, see `Iter`
+    pub fn iter(&self) -> Iter<'_, T, C> {
+        Iter { range: Range::new(&self.root, &self.comparator, Bound::Unbounded, Bound::Unbounded) }
+    }
+
+    // lazy in-order iterator over the keys within `range`
+    pub fn range<R: RangeBounds<T>>(&self, range: R) -> Range<'_, T, C> {
+        Range::new(&self.root, &self.comparator, range.start_bound().cloned(), range.end_bound().cloned())
+    }
+
+    // k-th smallest key, counting from 0
+    pub fn select(&self, k: usize) -> Option<T> {
+        let mut k = k;
+        let mut current = self.root.clone();
+        while let Some(node) = current {
+            let node_ref = node.borrow();
+            let left_size = size(&node_ref.left);
+            if k < left_size {
+                current = node_ref.left.clone();
+            } else if k > left_size {
+                k -= left_size + 1;
+                current = node_ref.right.clone();
+            } else {
+                return Some(node_ref.key.clone());
+            }
+        }
+        None
+    }
+
+    // lower median
+    pub fn median(&self) -> Option<T> {
+        if self.count == 0 {
+            return None;
+        }
+        self.select((self.count as usize - 1) / 2)
+    }
+
+    // nearest-rank percentile, `p` in [0, 100]
+    pub fn percentile(&self, p: f64) -> Option<T> {
+        let count = self.count as usize;
+        if count == 0 || !(0.0..=100.0).contains(&p) {
+            return None;
+        }
+        let rank = (p / 100.0 * count as f64).ceil() as usize;
+        self.select(rank.saturating_sub(1))
+    }
+
+    // checks ordering, coloring, black heights, parent pointers, cached sizes
+    // and the key count, reporting the first violation found
+    pub fn validate(&self) -> Result<(), InvariantViolation<T>> {
+        if let Some(ref root) = self.root {
+            let root = root.borrow();
+            if root.color == NodeColor::Red {
+                return Err(InvariantViolation::RedRoot { key: root.key.clone() });
+            }
+            if root.parent.is_some() {
+                return Err(InvariantViolation::BrokenParent { key: root.key.clone() });
+            }
+        }
+        let (_, actual) = self.validate_rec(&self.root, None, None)?;
+        if actual != self.count as usize {
+            return Err(InvariantViolation::CountMismatch { stored: self.count as usize, actual });
+        }
+        Ok(())
+    }
+
+    // returns the black height and size of the subtree
+    fn validate_rec(&self, tree: &Tree<T>, lower: Option<&T>, upper: Option<&T>) -> Result<(usize, usize), InvariantViolation<T>> {
+        let tree_node = match tree {
+            Some(tree_node) => tree_node,
+            None => return Ok((0, 0)),
+        };
+        let node = tree_node.borrow();
+        let key = &node.key;
+        let out_of_order = lower.is_some_and(|lower| self.comparator.compare(key, lower) != Ordering::Greater)
+            || upper.is_some_and(|upper| self.comparator.compare(key, upper) != Ordering::Less);
+        if out_of_order {
+            return Err(InvariantViolation::OutOfOrder { key: key.clone() });
+        }
+        for child in [&node.left, &node.right].into_iter().flatten() {
+            let child = child.borrow();
+            let parent_ok = child.parent().is_some_and(|p| Rc::ptr_eq(&p, tree_node));
+            if !parent_ok {
+                return Err(InvariantViolation::BrokenParent { key: child.key.clone() });
+            }
+            if node.color == NodeColor::Red && child.color == NodeColor::Red {
+                return Err(InvariantViolation::RedRed { key: key.clone() });
+            }
+        }
+        let (left_bh, left_size) = self.validate_rec(&node.left, lower, Some(key))?;
+        let (right_bh, right_size) = self.validate_rec(&node.right, Some(key), upper)?;
+        if left_bh != right_bh {
+            return Err(InvariantViolation::BlackHeightMismatch { key: key.clone(), left: left_bh, right: right_bh });
+        }
+        let actual_size = 1 + left_size + right_size;
+        if node.size != actual_size {
+            return Err(InvariantViolation::StaleSize { key: key.clone(), stored: node.size, actual: actual_size });
+        }
+        let bh = if node.color == NodeColor::Black { left_bh + 1 } else { left_bh };
+        Ok((bh, actual_size))
+    }
+
+    // largest key less than or equal to `key`
+    pub fn floor<Q>(&self, key: &Q) -> Option<T>
+    where T: std::borrow::Borrow<Q>, C: Comparator<Q>, Q: ?Sized
+    {
+        self.closest(key, true, true)
+    }
+
+    // smallest key greater than or equal to `key`
+    pub fn ceiling<Q>(&self, key: &Q) -> Option<T>
+    where T: std::borrow::Borrow<Q>, C: Comparator<Q>, Q: ?Sized
+    {
+        self.closest(key, false, true)
+    }
+
+    // largest key strictly less than `key`
+    pub fn predecessor<Q>(&self, key: &Q) -> Option<T>
+    where T: std::borrow::Borrow<Q>, C: Comparator<Q>, Q: ?Sized
+    {
+        self.closest(key, true, false)
+    }
+
+    // smallest key strictly greater than `key`
+    pub fn successor<Q>(&self, key: &Q) -> Option<T>
+    where T: std::borrow::Borrow<Q>, C: Comparator<Q>, Q: ?Sized
+    {
+        self.closest(key, false, false)
+    }
+
+    // nearest key below (or above) `key` on a single root-to-leaf descent,
+    // `key` itself counting only when `inclusive`
+    fn closest<Q>(&self, key: &Q, below: bool, inclusive: bool) -> Option<T>
+    where T: std::borrow::Borrow<Q>, C: Comparator<Q>, Q: ?Sized
+    {
+        let mut best = None;
+        let mut current = self.root.clone();
+        while let Some(node) = current {
+            let node_ref = node.borrow();
+            let order = self.cmp_key(&node_ref.key, key);
+            let wanted = if below { Ordering::Less } else { Ordering::Greater };
+            if order == wanted || (inclusive && order == Ordering::Equal) {
+                best = Some(node_ref.key.clone());
+                if order == Ordering::Equal {
+                    break;
+                }
+            }
+            current = if order == Ordering::Greater || (below && order == Ordering::Equal) {
+                node_ref.left.clone()
+            } else {
+                node_ref.right.clone()
+            };
+        }
+        best
+    }
+
+    pub fn preorder_traversal(&self) -> Vec<T> {
+        let mut result = Vec::new();
+        let mut stack: Vec<TreeNode<T>> = self.root.iter().cloned().collect();
+        while let Some(node) = stack.pop() {
+            result.push(node.borrow().key.clone());
+            if let Some(ref right) = node.borrow().right {
+                stack.push(right.clone());
+            }
+            if let Some(ref left) = node.borrow().left {
+                stack.push(left.clone());
+            }
+        }
+        result
+    }
+
+    pub fn levelorder_traversal(&self) -> Vec<T> {
+        if self.root.is_none() {
+            return Vec::new();
+        }
+        self.levelorder_nodes()
+            .into_iter()
+            .map(|node| node.unwrap().borrow().key.clone())
+            .collect()
+    }
+}
+
+impl<T> RBTree<T>
+where T: Ord+Clone
+{
+    // lazy iterator over the keys in self or `other`
+    pub fn union<'a>(&'a self, other: &'a Self) -> Union<Iter<'a, T>> {
+        Union::new(self.iter(), other.iter())
+    }
+
+    // lazy iterator over the keys in both self and `other`
+    pub fn intersection<'a>(&'a self, other: &'a Self) -> Intersection<Iter<'a, T>> {
+        Intersection::new(self.iter(), other.iter())
+    }
+
+    // lazy iterator over the keys in self but not in `other`
+    pub fn difference<'a>(&'a self, other: &'a Self) -> Difference<Iter<'a, T>> {
+        Difference::new(self.iter(), other.iter())
+    }
+
+    // lazy iterator over the keys in exactly one of self and `other`
+    pub fn symmetric_difference<'a>(&'a self, other: &'a Self) -> SymmetricDifference<Iter<'a, T>> {
+        SymmetricDifference::new(self.iter(), other.iter())
+    }
+
+    // new tree holding the union of self and `other`
+    pub fn union_tree(&self, other: &Self) -> Self {
+        Self::from_sorted_iter(self.union(other))
+    }
+
+    // new tree holding the intersection of self and `other`
+    pub fn intersection_tree(&self, other: &Self) -> Self {
+        Self::from_sorted_iter(self.intersection(other))
+    }
+
+    // new tree holding the keys of self that are not in `other`
+    pub fn difference_tree(&self, other: &Self) -> Self {
+        Self::from_sorted_iter(self.difference(other))
+    }
+
+    // new tree holding the symmetric difference of self and `other`
+    pub fn symmetric_difference_tree(&self, other: &Self) -> Self {
+        Self::from_sorted_iter(self.symmetric_difference(other))
+    }
+
+    // true if every key of self is also in `other`
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.count() <= other.count() && self.difference(other).next().is_none()
+    }
+
+    // true if every key of `other` is also in self
+    pub fn is_superset(&self, other: &Self) -> bool {
+        other.is_subset(self)
+    }
+
+    // true if self and `other` have no key in common
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.intersection(other).next().is_none()
+    }
+}
+
+impl<T, C> RBTree<T, C>
+where T: Display
+{
+    // print in-order traversal of tree
+    pub fn print_inorder(&self) {
+        if self.root.is_none() {
+            println!("None");
+            return;
+        }
+        let mut root = self.root.clone();
+        let mut stack: Vec<Tree<T>> = Vec::new();
+        while !stack.is_empty() || root.is_some() {
+            if root.is_some() {
+                stack.push(root.clone());
+                let p = root.as_ref().unwrap().borrow().left.clone();
+                root = p.clone();
+            } else {
+                let pop = stack.pop().unwrap();
+                print!(" {} ", pop.as_ref().unwrap().borrow().key);
+                root = pop.as_ref().unwrap().borrow().right.clone();
+            }
+        }
+        println!("\n");
+    }
+
+    pub fn print_preorder(&self) {
+        if self.root.is_none() {
+            println!("None");
+            return;
+        }
+        let mut root = self.root.clone();
+        let mut stack: Vec<Tree<T>> = Vec::new();
+        stack.push(root);
+        let mut cur: Tree<T>;
+        while !stack.is_empty() {
+            cur = stack.pop().unwrap();
+            root = cur.clone();
+            print!(" {} ", root.as_ref().unwrap().borrow().key);
+            let root_right = root.as_ref().unwrap().borrow().right.clone();
+            let root_left = root.as_ref().unwrap().borrow().left.clone();
+            if root_right.is_some() {
+                stack.push(root_right.clone());
+            }
+            if root_left.is_some() {
+                stack.push(root_left.clone());
+            }
+        }
+        println!("\n");
+    }
+
+    pub fn print_levelorder(&self) {
+        if self.root.is_none() {
+            println!("None");
+            return;
+        };
+        let levelorder_nodes = self.levelorder_nodes();
+        for node in levelorder_nodes {
+            print!(" {} ", node.unwrap().borrow().key);
+        }
+        println!("\n");
+    }
+
+    pub fn print_tree(&self) {
+        self.print_node(&self.root, "Root", "");
+    }
+
+    fn print_node(&self, node: &Tree<T>, node_type: &str, prefix: &str) {
+        if let Some(ref node) = node {
+            let color = match node.borrow().color {
+                NodeColor::Black => "black",
+                NodeColor::Red => "red",
+            };
+
+            let new_prefix = if node_type == "Root" {
+                "── "
+            } else {
+                if node_type == "L" { "├── " } else { "└── " }
+            };
+
+            println!("{}{}[{}] {}: {}", prefix, new_prefix, color, node_type, node.borrow().key);
+
+            let child_prefix = if node_type == "L" {
+                format!("{}│   ", prefix)
+            } else {
+                format!("{}    ", prefix)
+            };
+
+            self.print_node(&node.borrow().left, "L", &child_prefix);
+            self.print_node(&node.borrow().right, "R", &child_prefix);
+        }
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph RBTree {\n");
+        for node in self.levelorder_nodes().into_iter().flatten() {
+            let node = node.borrow();
+            let color = match node.color {
+                NodeColor::Black => "black",
+                NodeColor::Red => "red",
+            };
+            dot.push_str(&format!("    {} [color={}];\n", node.key, color));
+            if let Some(ref left) = node.left {
+                dot.push_str(&format!("    {} -> {} [label=\"L\"];\n", node.key, left.borrow().key));
+            }
+            if let Some(ref right) = node.right {
+                dot.push_str(&format!("    {} -> {} [label=\"R\"];\n", node.key, right.borrow().key));
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+impl<T, C> fmt::Display for RBTree<T, C>
+where T: Debug
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RBTree")
+         .field("root", &self.root)
+         .field("length", &self.count)
+         .finish()
+    }
+}
+
+impl<T, C> FromIterator<T> for RBTree<T, C>
+where C: Comparator<T>+Default
+{
+    // already sorted input skips the sort and goes straight to the O(n) build
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let comparator = C::default();
+        let mut keys: Vec<T> = iter.into_iter().collect();
+        if !keys.windows(2).all(|w| comparator.compare(&w[0], &w[1]) != Ordering::Greater) {
+            keys.sort_by(|a, b| comparator.compare(a, b));
+        }
+        Self::from_sorted_vec_with(keys, comparator)
+    }
+}
+
+impl<T, C> Extend<T> for RBTree<T, C>
+where C: Comparator<T>+Clone
+{
+    // sorted input that lies entirely above the current maximum is built
+    // separately and joined on, anything else is inserted key by key
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let keys: Vec<T> = iter.into_iter().collect();
+        let cmp = &self.comparator;
+        let sorted = keys.windows(2).all(|w| cmp.compare(&w[0], &w[1]) != Ordering::Greater);
+        let above_max = match (self.max(), keys.first()) {
+            (Some(max), Some(first)) => cmp.compare(&max.borrow().key, first) == Ordering::Less,
+            _ => true,
+        };
+        if sorted && above_max {
+            let mut other = Self::build_sorted(keys, self.comparator.clone(), self.refresh);
+            self.append(&mut other);
+        } else {
+            for key in keys {
+                self.insert(key);
+            }
+        }
+    }
+}
+
+/// In-order iterator over an `RBTree`, walking both ends with explicit
+/// stacks so it does not depend on the parent links. Each key is cloned as
+/// it is yielded: a node is only reached through its parent's `RefCell`, so
+/// a `Ref` into it could not outlive the step that found it.
+pub struct Iter<'a, T, C = Natural> {
+    range: Range<'a, T, C>,
+}
+
+impl<'a, T, C> Iterator for Iter<'a, T, C>
+where T: Clone, C: Comparator<T>
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.range.next()
+    }
+}
+
+impl<'a, T, C> DoubleEndedIterator for Iter<'a, T, C>
+where T: Clone, C: Comparator<T>
+{
+    fn next_back(&mut self) -> Option<T> {
+        self.range.next_back()
+    }
+}
+
+/// In-order iterator over the keys of an `RBTree` that fall within a range.
+/// Only the O(log n) nodes on the boundary paths are visited besides the
+/// ones yielded.
+pub struct Range<'a, T, C = Natural> {
+    front: Vec<TreeNode<T>>,
+    back: Vec<TreeNode<T>>,
+    // last nodes handed out from each end, used to detect when they meet
+    last_front: Tree<T>,
+    last_back: Tree<T>,
+    lower: Bound<T>,
+    upper: Bound<T>,
+    done: bool,
+    cmp: &'a C,
+    _tree: PhantomData<&'a Node<T>>,
+}
+
+impl<'a, T, C> Range<'a, T, C>
+where C: Comparator<T>
+{
+    fn new(root: &Tree<T>, cmp: &'a C, lower: Bound<T>, upper: Bound<T>) -> Self {
+        let mut range = Range {
+            cmp,
+            front: Vec::new(),
+            back: Vec::new(),
+            last_front: None,
+            last_back: None,
+            lower,
+            upper,
+            done: false,
+            _tree: PhantomData,
+        };
+
+        // seed the front with the path to the first key above the lower bound
+        let mut node = root.clone();
+        while let Some(cur) = node {
+            if range.above_lower(&cur.borrow().key) {
+                node = cur.borrow().left.clone();
+                range.front.push(cur);
+            } else {
+                node = cur.borrow().right.clone();
+            }
+        }
+
+        // and the back with the path to the last key below the upper bound
+        let mut node = root.clone();
+        while let Some(cur) = node {
+            if range.below_upper(&cur.borrow().key) {
+                node = cur.borrow().right.clone();
+                range.back.push(cur);
+            } else {
+                node = cur.borrow().left.clone();
+            }
+        }
+        range
+    }
+
+    fn above_lower(&self, key: &T) -> bool {
+        match self.lower {
+            Bound::Included(ref lower) => self.cmp.compare(key, lower) != Ordering::Less,
+            Bound::Excluded(ref lower) => self.cmp.compare(key, lower) == Ordering::Greater,
+            Bound::Unbounded => true,
+        }
+    }
+
+    fn below_upper(&self, key: &T) -> bool {
+        match self.upper {
+            Bound::Included(ref upper) => self.cmp.compare(key, upper) != Ordering::Greater,
+            Bound::Excluded(ref upper) => self.cmp.compare(key, upper) == Ordering::Less,
+            Bound::Unbounded => true,
+        }
+    }
+
+    fn push_left(&mut self, mut node: Tree<T>) {
+        while let Some(cur) = node {
+            node = cur.borrow().left.clone();
+            self.front.push(cur);
+        }
+    }
+
+    fn push_right(&mut self, mut node: Tree<T>) {
+        while let Some(cur) = node {
+            node = cur.borrow().right.clone();
+            self.back.push(cur);
+        }
+    }
+}
+
+impl<'a, T, C> Iterator for Range<'a, T, C>
+where T: Clone, C: Comparator<T>
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.done {
+            return None;
+        }
+        let node = self.front.pop()?;
+        if self.last_back.as_ref().is_some_and(|last| Rc::ptr_eq(last, &node))
+            || !self.below_upper(&node.borrow().key) {
+            self.done = true;
+            return None;
+        }
+        self.push_left(node.borrow().right.clone());
+        let key = node.borrow().key.clone();
+        self.last_front = Some(node);
+        Some(key)
+    }
+}
+
+impl<'a, T, C> DoubleEndedIterator for Range<'a, T, C>
+where T: Clone, C: Comparator<T>
+{
+    fn next_back(&mut self) -> Option<T> {
+        if self.done {
+            return None;
+        }
+        let node = self.back.pop()?;
+        if self.last_front.as_ref().is_some_and(|last| Rc::ptr_eq(last, &node))
+            || !self.above_lower(&node.borrow().key) {
+            self.done = true;
+            return None;
+        }
+        self.push_right(node.borrow().left.clone());
+        let key = node.borrow().key.clone();
+        self.last_back = Some(node);
+        Some(key)
+    }
+}
+
+impl<'a, T, C> IntoIterator for &'a RBTree<T, C>
+where T: Clone, C: Comparator<T>
+{
+    type Item = T;
+    type IntoIter = Iter<'a, T, C>;
+
+    fn into_iter(self) -> Iter<'a, T, C> {
+        self.iter()
+    }
+}
+
+/// Owning iterator over the keys of an `RBTree`, in order.
+pub struct IntoIter<T> {
+    keys: std::vec::IntoIter<T>,
+}
+
+impl<T, C> IntoIterator for RBTree<T, C>
+where T: Clone
+{
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    // unlinks the nodes in order and moves their keys out, cloning only the
+    // keys of nodes still shared through a handle from `search`
+    fn into_iter(mut self) -> IntoIter<T> {
+        IntoIter { keys: take_keys(self.root.take(), self.count as usize).into_iter() }
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.keys.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.keys.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.keys.next_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+/// Position among the keys of an `RBTree`, from `RBTree::cursor_front`,
+/// `cursor_back` or `cursor_at`. Steps to either neighbour through the
+/// parent links, in O(1) amortized. Past either end it rests on a ghost
+/// position, from which `move_next` wraps to the first key and `move_prev`
+/// to the last.
+pub struct Cursor<'a, T, C = Natural> {
+    tree: &'a RBTree<T, C>,
+    current: Tree<T>,
+}
+
+impl<'a, T, C> Cursor<'a, T, C>
+where T: Clone
+{
+    // key under the cursor, none on the ghost position
+    pub fn current(&self) -> Option<T> {
+        self.current.as_ref().map(|node| node.borrow().key.clone())
+    }
+
+    pub fn move_next(&mut self) {
+        self.current = self.tree.step(&self.current, true);
+    }
+
+    pub fn move_prev(&mut self) {
+        self.current = self.tree.step(&self.current, false);
+    }
+
+    // key the cursor would be on after `move_next`
+    pub fn peek_next(&self) -> Option<T> {
+        self.tree.step(&self.current, true).map(|node| node.borrow().key.clone())
+    }
+
+    pub fn peek_prev(&self) -> Option<T> {
+        self.tree.step(&self.current, false).map(|node| node.borrow().key.clone())
+    }
+}
+
+/// Cursor that can also remove the key it is on, from
+/// `RBTree::cursor_front_mut`, `cursor_back_mut` or `cursor_at_mut`.
+pub struct CursorMut<'a, T, C = Natural> {
+    tree: &'a mut RBTree<T, C>,
+    current: Tree<T>,
+}
+
+impl<'a, T, C> CursorMut<'a, T, C>
+where T: Clone, C: Comparator<T>
+{
+    // read-only view of the same position
+    pub fn as_cursor(&self) -> Cursor<'_, T, C> {
+        Cursor { tree: self.tree, current: self.current.clone() }
+    }
+
+    pub fn current(&self) -> Option<T> {
+        self.as_cursor().current()
+    }
+
+    pub fn move_next(&mut self) {
+        self.current = self.tree.step(&self.current, true);
+    }
+
+    pub fn move_prev(&mut self) {
+        self.current = self.tree.step(&self.current, false);
+    }
+
+    pub fn peek_next(&self) -> Option<T> {
+        self.as_cursor().peek_next()
+    }
+
+    pub fn peek_prev(&self) -> Option<T> {
+        self.as_cursor().peek_prev()
+    }
+
+    // unlinks the node under the cursor, which moves on to the next key,
+    // and returns its key. The node is unlinked where it stands, without
+    // searching for it from the root.
+    pub fn remove_current(&mut self) -> Result<T, TreeError> {
+        let node = self.current.take().ok_or(TreeError::KeyNotFound)?;
+        self.current = RBTree::<T, C>::neighbour(&node, true);
+        Ok(self.tree.take_node(node))
+    }
+}
+
+impl<T, C> OrderedSet<T> for RBTree<T, C>
+where T: Clone, C: Comparator<T>
+{
+    fn insert(&mut self, value: T) -> bool {
+        RBTree::insert(self, value)
+    }
+
+    fn remove(&mut self, value: &T) -> bool {
+        self.delete(value).is_ok()
+    }
+
+    fn contains(&self, value: &T) -> bool {
+        self.search_element(value)
+    }
+
+    fn len(&self) -> usize {
+        self.count() as usize
+    }
+
+    fn height(&self) -> usize {
+        RBTree::height(self) as usize
+    }
+
+    fn count_leaves(&self) -> usize {
+        self.leaves() as usize
+    }
+
+    fn min(&self) -> Option<T> {
+        RBTree::min(self).map(|node| node.borrow().key.clone())
+    }
+
+    fn max(&self) -> Option<T> {
+        RBTree::max(self).map(|node| node.borrow().key.clone())
+    }
+
+    fn pop_first(&mut self) -> Option<T> {
+        RBTree::pop_first(self)
+    }
+
+    fn pop_last(&mut self) -> Option<T> {
+        RBTree::pop_last(self)
+    }
+
+    fn inorder_traversal(&self) -> Vec<T> {
+        RBTree::inorder_traversal(self)
+    }
+
+    fn preorder_traversal(&self) -> Vec<T> {
+        RBTree::preorder_traversal(self)
+    }
+
+    fn levelorder_traversal(&self) -> Vec<T> {
+        RBTree::levelorder_traversal(self)
+    }
+
+    fn validate(&self) -> Result<(), InvariantViolation<T>> {
+        RBTree::validate(self)
+    }
+}
+
+impl<T, C> PrintableSet<T> for RBTree<T, C>
+where T: Display+Debug+Clone, C: Comparator<T>
+{
+    fn print_tree(&self) {
+        RBTree::print_tree(self)
+    }
+
+    fn to_dot(&self) -> String {
+        RBTree::to_dot(self)
+    }
+}
@@ -0,0 +1,146 @@
+use std::cmp::Ordering;
+use std::iter::Peekable;
+
+// Lazy set operations over two iterators that yield strictly increasing
+// values, as the in-order iterators of both trees do. Each step looks at the
+// heads of both sides, so a full pass is O(n + m).
+
+/// Values in either input.
+pub struct Union<I: Iterator> {
+    a: Peekable<I>,
+    b: Peekable<I>,
+}
+
+/// Values in both inputs.
+pub struct Intersection<I: Iterator> {
+    a: Peekable<I>,
+    b: Peekable<I>,
+}
+
+/// Values in the first input but not the second.
+pub struct Difference<I: Iterator> {
+    a: Peekable<I>,
+    b: Peekable<I>,
+}
+
+/// Values in exactly one of the inputs.
+pub struct SymmetricDifference<I: Iterator> {
+    a: Peekable<I>,
+    b: Peekable<I>,
+}
+
+impl<I: Iterator> Union<I> {
+    pub(crate) fn new(a: I, b: I) -> Self {
+        Union { a: a.peekable(), b: b.peekable() }
+    }
+}
+
+impl<I: Iterator> Intersection<I> {
+    pub(crate) fn new(a: I, b: I) -> Self {
+        Intersection { a: a.peekable(), b: b.peekable() }
+    }
+}
+
+impl<I: Iterator> Difference<I> {
+    pub(crate) fn new(a: I, b: I) -> Self {
+        Difference { a: a.peekable(), b: b.peekable() }
+    }
+}
+
+impl<I: Iterator> SymmetricDifference<I> {
+    pub(crate) fn new(a: I, b: I) -> Self {
+        SymmetricDifference { a: a.peekable(), b: b.peekable() }
+    }
+}
+
+impl<T: Ord, I: Iterator<Item = T>> Iterator for Union<I> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let order = match (self.a.peek(), self.b.peek()) {
+            (Some(x), Some(y)) => x.cmp(y),
+            (Some(_), None) => Ordering::Less,
+            (None, _) => Ordering::Greater,
+        };
+        match order {
+            Ordering::Less => self.a.next(),
+            Ordering::Greater => self.b.next(),
+            Ordering::Equal => {
+                self.b.next();
+                self.a.next()
+            }
+        }
+    }
+}
+
+impl<T: Ord, I: Iterator<Item = T>> Iterator for Intersection<I> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        loop {
+            let order = match (self.a.peek(), self.b.peek()) {
+                (Some(x), Some(y)) => x.cmp(y),
+                _ => return None,
+            };
+            match order {
+                Ordering::Less => {
+                    self.a.next();
+                },
+                Ordering::Greater => {
+                    self.b.next();
+                },
+                Ordering::Equal => {
+                    self.b.next();
+                    return self.a.next();
+                }
+            }
+        }
+    }
+}
+
+impl<T: Ord, I: Iterator<Item = T>> Iterator for Difference<I> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        loop {
+            let order = match (self.a.peek(), self.b.peek()) {
+                (Some(x), Some(y)) => x.cmp(y),
+                (Some(_), None) => Ordering::Less,
+                (None, _) => return None,
+            };
+            match order {
+                Ordering::Less => return self.a.next(),
+                Ordering::Greater => {
+                    self.b.next();
+                },
+                Ordering::Equal => {
+                    self.a.next();
+                    self.b.next();
+                }
+            }
+        }
+    }
+}
+
+impl<T: Ord, I: Iterator<Item = T>> Iterator for SymmetricDifference<I> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        loop {
+            let order = match (self.a.peek(), self.b.peek()) {
+                (Some(x), Some(y)) => x.cmp(y),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => return None,
+            };
+            match order {
+                Ordering::Less => return self.a.next(),
+                Ordering::Greater => return self.b.next(),
+                Ordering::Equal => {
+                    self.a.next();
+                    self.b.next();
+                }
+            }
+        }
+    }
+}
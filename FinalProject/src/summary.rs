@@ -0,0 +1,180 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Bound};
+
+// Recomputes data a key caches about its subtree, such as the largest
+// endpoint below an interval, from the keys of its children. Trees built
+// with a refresh hook call it bottom-up wherever subtree sizes change.
+pub(crate) type Refresh<T> = fn(&mut T, Option<&T>, Option<&T>);
+
+/// Monoid summarizing a run of consecutive keys, cached in every node of an
+/// `RBSummaryTree` or `AVLSummaryTree` for the keys of its subtree.
+/// `combine` must be associative and `identity` neutral for it, but need
+/// not be commutative: the left operand always covers the smaller keys.
+pub trait Summary<T>: Clone {
+    // summary of an empty run
+    fn identity() -> Self;
+    // summary of the single key
+    fn of(key: &T) -> Self;
+    // summary of two adjacent runs, `self` holding the smaller keys
+    fn combine(&self, other: &Self) -> Self;
+}
+
+/// Number of keys.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Count(pub usize);
+
+impl<T> Summary<T> for Count {
+    fn identity() -> Self {
+        Count(0)
+    }
+
+    fn of(_: &T) -> Self {
+        Count(1)
+    }
+
+    fn combine(&self, other: &Self) -> Self {
+        Count(self.0 + other.0)
+    }
+}
+
+/// Sum of the keys, starting from `T::default()`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Sum<T>(pub T);
+
+impl<T> Summary<T> for Sum<T>
+where T: Clone + Default + Add<Output = T>
+{
+    fn identity() -> Self {
+        Sum(T::default())
+    }
+
+    fn of(key: &T) -> Self {
+        Sum(key.clone())
+    }
+
+    fn combine(&self, other: &Self) -> Self {
+        Sum(self.0.clone() + other.0.clone())
+    }
+}
+
+/// Smallest key, `None` for an empty run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Min<T>(pub Option<T>);
+
+impl<T: Ord + Clone> Summary<T> for Min<T> {
+    fn identity() -> Self {
+        Min(None)
+    }
+
+    fn of(key: &T) -> Self {
+        Min(Some(key.clone()))
+    }
+
+    fn combine(&self, other: &Self) -> Self {
+        match (&self.0, &other.0) {
+            (Some(a), Some(b)) => Min(Some(std::cmp::min(a, b).clone())),
+            (Some(_), None) => self.clone(),
+            (None, _) => other.clone(),
+        }
+    }
+}
+
+/// Largest key, `None` for an empty run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Max<T>(pub Option<T>);
+
+impl<T: Ord + Clone> Summary<T> for Max<T> {
+    fn identity() -> Self {
+        Max(None)
+    }
+
+    fn of(key: &T) -> Self {
+        Max(Some(key.clone()))
+    }
+
+    fn combine(&self, other: &Self) -> Self {
+        match (&self.0, &other.0) {
+            (Some(a), Some(b)) => Max(Some(std::cmp::max(a, b).clone())),
+            (Some(_), None) => self.clone(),
+            (None, _) => other.clone(),
+        }
+    }
+}
+
+// Key stored inside a summary tree next to the summary of its subtree. Only
+// `key` takes part in ordering, like `MapKey`.
+#[derive(Clone, Debug)]
+pub(crate) struct Annotated<T, S> {
+    pub(crate) key: T,
+    pub(crate) summary: S,
+}
+
+impl<T, S: Summary<T>> Annotated<T, S> {
+    pub(crate) fn new(key: T) -> Self {
+        Annotated { summary: S::of(&key), key }
+    }
+}
+
+impl<T: PartialEq, S> PartialEq for Annotated<T, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl<T: Eq, S> Eq for Annotated<T, S> {}
+
+impl<T: Ord, S> PartialOrd for Annotated<T, S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord, S> Ord for Annotated<T, S> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+// lets a tree of annotated keys be searched with a plain `&T`
+impl<T, S> Borrow<T> for Annotated<T, S> {
+    fn borrow(&self) -> &T {
+        &self.key
+    }
+}
+
+impl<T: fmt::Display, S> fmt::Display for Annotated<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.key.fmt(f)
+    }
+}
+
+// refresh hook of a summary tree: the subtree summary is the left child's,
+// then the key's own, then the right child's
+pub(crate) fn refresh_summary<T, S: Summary<T>>(node: &mut Annotated<T, S>, left: Option<&Annotated<T, S>>, right: Option<&Annotated<T, S>>) {
+    let mut summary = S::of(&node.key);
+    if let Some(left) = left {
+        summary = left.summary.combine(&summary);
+    }
+    if let Some(right) = right {
+        summary = summary.combine(&right.summary);
+    }
+    node.summary = summary;
+}
+
+pub(crate) fn above_lower<T: Ord>(key: &T, lower: Bound<&T>) -> bool {
+    match lower {
+        Bound::Included(lower) => key >= lower,
+        Bound::Excluded(lower) => key > lower,
+        Bound::Unbounded => true,
+    }
+}
+
+pub(crate) fn below_upper<T: Ord>(key: &T, upper: Bound<&T>) -> bool {
+    match upper {
+        Bound::Included(upper) => key <= upper,
+        Bound::Excluded(upper) => key < upper,
+        Bound::Unbounded => true,
+    }
+}
@@ -0,0 +1,42 @@
+// Keys that are not `Display`: AVLTree and the wrappers on top of it look
+// them up through borrowed forms, like `&str` for `String` keys.
+
+use FinalProject::avlmap::AVLMap;
+use FinalProject::avlmultiset::AVLMultiset;
+use FinalProject::avltree::AVLTree;
+
+const WORDS: [&str; 8] = ["pear", "fig", "apple", "plum", "kiwi", "lime", "date", "cherry"];
+
+#[test]
+fn string_keys_are_looked_up_by_str() {
+    let mut tree: AVLTree<String> = WORDS.iter().map(|word| word.to_string()).collect();
+    assert!(tree.validate().is_ok());
+    assert!(tree.search("kiwi"));
+    assert!(!tree.search("mango"));
+    assert_eq!(tree.get("plum"), Some("plum".to_string()));
+    assert_eq!(tree.delete("apple"), Ok("apple".to_string()));
+    assert!(tree.delete("apple").is_err());
+    assert!(tree.validate().is_ok());
+    assert_eq!(tree.iter().next().as_deref(), Some("cherry"));
+}
+
+#[test]
+fn keys_without_display() {
+    let mut tree: AVLTree<Vec<u8>> = (0..100u8).map(|i| vec![i % 10, i]).collect();
+    for i in (0..100u8).step_by(3) {
+        assert!(tree.delete(&[i % 10, i][..]).is_ok());
+    }
+    assert!(tree.validate().is_ok());
+    assert_eq!(tree.count(), 66);
+
+    let mut map: AVLMap<Vec<u8>, usize> = AVLMap::new();
+    for (i, word) in WORDS.iter().enumerate() {
+        map.insert(word.as_bytes().to_vec(), i);
+    }
+    assert_eq!(map.get(&b"lime".to_vec()), Some(&5));
+    assert_eq!(map.remove(&b"fig".to_vec()), Some(1));
+    assert!(!map.contains_key(&b"fig".to_vec()));
+
+    let bag: AVLMultiset<Vec<u8>> = [vec![1], vec![2], vec![1]].into_iter().collect();
+    assert_eq!(bag.count_of(&vec![1]), 2);
+}
@@ -0,0 +1,123 @@
+// Trees ordered by something other than the keys' own `Ord`: a field of a
+// record, reverse order, and case-insensitive strings.
+
+use std::cmp::Ordering;
+
+use FinalProject::avltree::AVLTree;
+use FinalProject::rbtree::RBTree;
+
+#[derive(Clone, Debug, PartialEq)]
+struct Task {
+    priority: u32,
+    name: &'static str,
+}
+
+impl std::fmt::Display for Task {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}:{}", self.priority, self.name)
+    }
+}
+
+fn task(priority: u32, name: &'static str) -> Task {
+    Task { priority, name }
+}
+
+fn by_priority(a: &Task, b: &Task) -> Ordering {
+    a.priority.cmp(&b.priority)
+}
+
+#[test]
+fn records_ordered_by_field() {
+    let tasks = [task(3, "deploy"), task(1, "build"), task(4, "notify"), task(2, "test")];
+    let mut rb = RBTree::with_comparator(by_priority as fn(&Task, &Task) -> Ordering);
+    let mut avl = AVLTree::with_comparator(by_priority as fn(&Task, &Task) -> Ordering);
+    for t in tasks.iter().cloned() {
+        assert!(rb.insert(t.clone()));
+        assert!(avl.insert(t));
+    }
+    // only the priority is compared, so a different name is still a duplicate
+    assert!(!rb.insert(task(2, "lint")));
+    assert!(!avl.insert(task(2, "lint")));
+
+    let names: Vec<_> = rb.iter().map(|t| t.name).collect();
+    assert_eq!(names, ["build", "test", "deploy", "notify"]);
+    assert_eq!(avl.inorder_traversal().iter().map(|t| t.name).collect::<Vec<_>>(), names);
+
+    let probe = task(3, "");
+    assert_eq!(rb.get(&probe).map(|t| t.name), Some("deploy"));
+    assert_eq!(avl.get(&probe).map(|t| t.name), Some("deploy"));
+    assert_eq!(rb.rank(&probe), 2);
+    assert_eq!(avl.rank(&probe), 2);
+    assert_eq!(rb.range(task(2, "")..task(4, "")).count(), 2);
+    assert_eq!(avl.range(task(2, "")..task(4, "")).count(), 2);
+
+    assert_eq!(rb.delete(&task(1, "")), Ok(task(1, "build")));
+    assert_eq!(avl.delete(&task(1, "")), Ok(task(1, "build")));
+    assert!(rb.validate().is_ok());
+    assert!(avl.validate().is_ok());
+}
+
+#[test]
+fn reverse_order() {
+    let reverse = |a: &i32, b: &i32| b.cmp(a);
+    let mut rb = RBTree::with_comparator(reverse);
+    let mut avl = AVLTree::with_comparator(reverse);
+    for i in 0..200 {
+        rb.insert((i * 37) % 200);
+        avl.insert((i * 37) % 200);
+    }
+    assert!(rb.validate().is_ok());
+    assert!(avl.validate().is_ok());
+    assert_eq!(rb.iter().take(3).collect::<Vec<_>>(), [199, 198, 197]);
+    assert_eq!(avl.iter().take(3).collect::<Vec<_>>(), [199, 198, 197]);
+    assert_eq!(rb.iter().next(), Some(199));
+    assert_eq!(avl.min(), Some(199));
+
+    // "below" follows the comparator, so floor is the next larger integer
+    assert_eq!(rb.floor(&-1), Some(0));
+    assert_eq!(avl.floor(&-1), Some(0));
+    assert_eq!(rb.count_range(..100), 99);
+    assert_eq!(avl.count_range(..100), 99);
+
+    for i in (0..200).step_by(2) {
+        assert!(rb.delete(&i).is_ok());
+        assert!(avl.delete(&i).is_ok());
+    }
+    assert!(rb.validate().is_ok());
+    assert!(avl.validate().is_ok());
+
+    let high = rb.split_off(&100);
+    assert_eq!(rb.count(), 50);
+    assert_eq!(high.iter().next(), Some(99));
+    let low = avl.split_off(&100);
+    assert_eq!(avl.count(), 50);
+    assert_eq!(low.iter().next(), Some(99));
+}
+
+#[test]
+fn case_insensitive_strings() {
+    let cmp = |a: &String, b: &String| a.to_lowercase().cmp(&b.to_lowercase());
+    let mut rb = RBTree::with_comparator(cmp);
+    let mut avl = AVLTree::with_comparator(cmp);
+    for word in ["Banana", "apple", "Cherry", "APPLE", "banana", "date"] {
+        rb.insert(word.to_string());
+        avl.insert(word.to_string());
+    }
+    assert_eq!(rb.inorder_traversal(), ["apple", "Banana", "Cherry", "date"]);
+    assert_eq!(avl.inorder_traversal(), ["apple", "Banana", "Cherry", "date"]);
+    assert!(rb.search_element(&"CHERRY".to_string()));
+    assert!(avl.search(&"CHERRY".to_string()));
+
+    // sorted input joined on the end keeps using the tree's ordering
+    rb.extend(["Elder".to_string(), "fig".to_string()]);
+    avl.extend(["Elder".to_string(), "fig".to_string()]);
+    assert_eq!(rb.iter().next_back(), Some("fig".to_string()));
+    assert_eq!(avl.max(), Some("fig".to_string()));
+
+    let mut other = RBTree::with_comparator(cmp);
+    other.insert("BANANA".to_string());
+    other.insert("grape".to_string());
+    rb.append(&mut other);
+    assert_eq!(rb.count(), 7);
+    assert!(rb.validate().is_ok());
+}
@@ -0,0 +1,185 @@
+// Cursors on both trees: seeking, stepping across the ghost position, and
+// removing while walking, checked against an index into a sorted vector.
+
+use proptest::prelude::*;
+use FinalProject::avltree::AVLTree;
+use FinalProject::error::TreeError;
+use FinalProject::rbtree::RBTree;
+
+#[derive(Clone, Debug)]
+enum Op {
+    Next,
+    Prev,
+    Remove,
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        4 => Just(Op::Next),
+        3 => Just(Op::Prev),
+        2 => Just(Op::Remove),
+    ]
+}
+
+// position in `keys`, where `keys.len()` is the ghost
+fn step(position: usize, len: usize, forward: bool) -> usize {
+    let ghost = len;
+    match (position == ghost, forward) {
+        (true, true) => if len == 0 { ghost } else { 0 },
+        (true, false) => if len == 0 { ghost } else { len - 1 },
+        (false, true) => position + 1,
+        (false, false) => if position == 0 { ghost } else { position - 1 },
+    }
+}
+
+#[test]
+fn seek_and_step() {
+    let rb: RBTree<u32> = (1..=10).map(|i| i * 10).collect();
+    let avl: AVLTree<u32> = (1..=10).map(|i| i * 10).collect();
+
+    let mut cursor = rb.cursor_at(&35);
+    assert_eq!(cursor.current(), Some(40));
+    assert_eq!(cursor.peek_prev(), Some(30));
+    cursor.move_prev();
+    cursor.move_prev();
+    assert_eq!(cursor.current(), Some(20));
+    let cursor = avl.cursor_at(&50);
+    assert_eq!(cursor.current(), Some(50));
+    assert_eq!(cursor.peek_next(), Some(60));
+
+    // off the end onto the ghost, then wrapping around
+    let mut cursor = rb.cursor_back();
+    cursor.move_next();
+    assert_eq!(cursor.current(), None);
+    assert_eq!(cursor.peek_next(), Some(10));
+    cursor.move_next();
+    assert_eq!(cursor.current(), Some(10));
+    let mut cursor = avl.cursor_front();
+    cursor.move_prev();
+    assert_eq!(cursor.current(), None);
+    cursor.move_prev();
+    assert_eq!(cursor.current(), Some(100));
+    assert_eq!(avl.cursor_at(&101).current(), None);
+
+    let mut walked = Vec::new();
+    let mut cursor = avl.cursor_back();
+    while let Some(value) = cursor.current() {
+        walked.push(value);
+        cursor.move_prev();
+    }
+    assert_eq!(walked, avl.iter().rev().collect::<Vec<_>>());
+}
+
+#[test]
+fn purge_while_walking() {
+    let mut rb: RBTree<u32> = (0..100).collect();
+    let mut avl: AVLTree<u32> = (0..100).collect();
+
+    let mut cursor = rb.cursor_at_mut(&20);
+    while cursor.current().is_some_and(|key| key < 80) {
+        if cursor.current().unwrap() % 3 == 0 {
+            cursor.remove_current().unwrap();
+        } else {
+            cursor.move_next();
+        }
+    }
+    assert_eq!(cursor.current(), Some(80));
+    assert!(rb.validate().is_ok());
+    assert_eq!(rb.count(), 80);
+
+    let mut cursor = avl.cursor_front_mut();
+    while let Some(value) = cursor.current() {
+        if value % 2 == 1 {
+            assert_eq!(cursor.remove_current(), Ok(value));
+        } else {
+            cursor.move_next();
+        }
+    }
+    assert_eq!(cursor.remove_current(), Err(TreeError::KeyNotFound));
+    assert!(avl.validate().is_ok());
+    assert_eq!(avl.iter().collect::<Vec<_>>(), (0..100).step_by(2).collect::<Vec<_>>());
+}
+
+#[test]
+fn removing_keeps_the_path_in_step() {
+    // every removal rebalances somewhere on the path, and the cursor's
+    // neighbours are read off the repaired path right after
+    for start in [0, 1, 255, 500, 777] {
+        let mut model: Vec<u32> = (0..1000).collect();
+        let mut avl: AVLTree<u32> = model.iter().copied().collect();
+        let mut cursor = avl.cursor_at_mut(&start);
+        let mut position = start as usize;
+        while position < model.len() {
+            if model[position] % 4 == 3 {
+                position += 1;
+                cursor.move_next();
+            } else {
+                assert_eq!(cursor.remove_current(), Ok(model.remove(position)));
+            }
+            assert_eq!(cursor.current(), model.get(position).copied());
+            assert_eq!(cursor.peek_prev(), position.checked_sub(1).map(|prev| model[prev]));
+        }
+        drop(cursor);
+        assert!(avl.validate().is_ok());
+        assert_eq!(avl.iter().collect::<Vec<_>>(), model);
+    }
+}
+
+#[test]
+fn held_node_is_removed_all_the_same() {
+    let mut rb: RBTree<u32> = (0..10).collect();
+    let handle = rb.search(&4);
+    let mut cursor = rb.cursor_at_mut(&4);
+    assert_eq!(cursor.remove_current(), Ok(4));
+    assert_eq!(cursor.current(), Some(5));
+    drop(cursor);
+    assert!(handle.is_some());
+    assert!(rb.validate().is_ok() && !rb.search_element(&4));
+}
+
+proptest! {
+    #[test]
+    fn cursors_match_an_index(keys in prop::collection::btree_set(0..200u16, 0..100), ops in prop::collection::vec(op(), 1..100)) {
+        let mut model: Vec<u16> = keys.into_iter().collect();
+        let mut rb: RBTree<u16> = model.iter().copied().collect();
+        let mut avl: AVLTree<u16> = model.iter().copied().collect();
+        let mut rb_cursor = rb.cursor_front_mut();
+        let mut avl_cursor = avl.cursor_front_mut();
+        let mut position = 0;
+        for op in &ops {
+            match *op {
+                Op::Next => {
+                    rb_cursor.move_next();
+                    avl_cursor.move_next();
+                    position = step(position, model.len(), true);
+                },
+                Op::Prev => {
+                    rb_cursor.move_prev();
+                    avl_cursor.move_prev();
+                    position = step(position, model.len(), false);
+                },
+                Op::Remove => {
+                    let expected = model.get(position).copied().ok_or(TreeError::KeyNotFound);
+                    if position < model.len() {
+                        model.remove(position);
+                    }
+                    prop_assert_eq!(rb_cursor.remove_current(), expected);
+                    prop_assert_eq!(avl_cursor.remove_current(), expected);
+                },
+            }
+            let expected = model.get(position).copied();
+            prop_assert_eq!(rb_cursor.current(), expected);
+            prop_assert_eq!(avl_cursor.current(), expected);
+            let ghost = model.len();
+            let next = model.get(step(position, ghost, true)).copied();
+            prop_assert_eq!(rb_cursor.peek_next(), next);
+            prop_assert_eq!(avl_cursor.peek_prev(), model.get(step(position, ghost, false)).copied());
+        }
+        drop(rb_cursor);
+        drop(avl_cursor);
+        prop_assert!(rb.validate().is_ok());
+        prop_assert!(avl.validate().is_ok());
+        prop_assert_eq!(rb.iter().collect::<Vec<_>>(), model.clone());
+        prop_assert_eq!(avl.iter().collect::<Vec<_>>(), model);
+    }
+}
@@ -0,0 +1,356 @@
+// Model-based tests: random operation sequences are applied to each tree and
+// to a `BTreeSet`, and every observable result is compared after each step.
+// proptest shrinks a failing sequence down to a minimal reproduction.
+
+use std::collections::BTreeSet;
+use std::ops::Bound;
+
+use proptest::prelude::*;
+use FinalProject::arena_avltree::ArenaAVLTree;
+use FinalProject::arena_rbtree::ArenaRBTree;
+use FinalProject::avltree::AVLTree;
+use FinalProject::ordered_set::OrderedSet;
+use FinalProject::rbtree::RBTree;
+
+#[derive(Clone, Debug)]
+enum Op {
+    Insert(u16),
+    Remove(u16),
+    Contains(u16),
+}
+
+// a small key space so that removes and repeated inserts hit existing keys
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        3 => (0..200u16).prop_map(Op::Insert),
+        2 => (0..200u16).prop_map(Op::Remove),
+        1 => (0..200u16).prop_map(Op::Contains),
+    ]
+}
+
+// AVL trees are at most about 1.44 log2(n + 2) levels deep
+fn avl_max_height(len: usize) -> usize {
+    (1.4405 * ((len + 2) as f64).log2() - 0.3277).floor() as usize
+}
+
+// red-black trees are at most 2 log2(n + 1) levels deep
+fn rb_max_height(len: usize) -> usize {
+    (2.0 * ((len + 1) as f64).log2()).floor() as usize
+}
+
+fn check_against_model<S: OrderedSet<u16>>(
+    mut set: S,
+    ops: &[Op],
+    max_height: fn(usize) -> usize,
+) -> Result<(), TestCaseError> {
+    let mut model = BTreeSet::new();
+    for op in ops {
+        match *op {
+            Op::Insert(key) => prop_assert_eq!(set.insert(key), model.insert(key), "insert {}", key),
+            Op::Remove(key) => prop_assert_eq!(set.remove(&key), model.remove(&key), "remove {}", key),
+            Op::Contains(key) => prop_assert_eq!(set.contains(&key), model.contains(&key), "contains {}", key),
+        }
+
+        if let Err(violation) = set.validate() {
+            return Err(TestCaseError::fail(format!("after {:?}: {}", op, violation)));
+        }
+        prop_assert_eq!(set.len(), model.len());
+        prop_assert_eq!(set.is_empty(), model.is_empty());
+        prop_assert!(set.height() <= max_height(model.len()), "height {} for {} keys", set.height(), model.len());
+        prop_assert_eq!(set.min(), model.first().copied());
+        prop_assert_eq!(set.max(), model.last().copied());
+
+        let expected: Vec<u16> = model.iter().copied().collect();
+        prop_assert_eq!(set.inorder_traversal(), expected.clone());
+        let mut preorder = set.preorder_traversal();
+        preorder.sort();
+        prop_assert_eq!(preorder, expected.clone());
+        let mut levelorder = set.levelorder_traversal();
+        levelorder.sort();
+        prop_assert_eq!(levelorder, expected);
+    }
+    Ok(())
+}
+
+// one end of a range: included, excluded or open
+fn bound() -> impl Strategy<Value = Bound<u16>> {
+    prop_oneof![
+        (0..520u16).prop_map(Bound::Included),
+        (0..520u16).prop_map(Bound::Excluded),
+        Just(Bound::Unbounded),
+    ]
+}
+
+// every combination of ends, except those `BTreeSet::range` panics on:
+// a start past the end, or the same key excluded at both ends
+fn bounds() -> impl Strategy<Value = (Bound<u16>, Bound<u16>)> {
+    (bound(), bound()).prop_filter("BTreeSet::range rejects it", |range| match *range {
+        (Bound::Included(start) | Bound::Excluded(start), Bound::Included(end) | Bound::Excluded(end)) => {
+            start < end || (start == end && !matches!(range, (Bound::Excluded(_), Bound::Excluded(_))))
+        }
+        _ => true,
+    })
+}
+
+// pulls from both ends of `range` in the order given by `from_back`, over
+// and over, checking each key against the model's range and then that both
+// ends stay exhausted
+fn walk_range<I: DoubleEndedIterator<Item = u16>>(
+    mut range: I,
+    model: &BTreeSet<u16>,
+    bounds: (Bound<u16>, Bound<u16>),
+    from_back: &[bool],
+) -> Result<(), TestCaseError> {
+    let mut expected = model.range(bounds).copied();
+    for &back in from_back.iter().cycle() {
+        let (got, want) = if back {
+            (range.next_back(), expected.next_back())
+        } else {
+            (range.next(), expected.next())
+        };
+        prop_assert_eq!(got, want, "{:?} from the {}", bounds, if back { "back" } else { "front" });
+        if want.is_none() {
+            break;
+        }
+    }
+    prop_assert_eq!(range.next(), None);
+    prop_assert_eq!(range.next_back(), None);
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn avltree_matches_btreeset(ops in prop::collection::vec(op(), 1..300)) {
+        check_against_model(AVLTree::new(), &ops, avl_max_height)?;
+    }
+
+    #[test]
+    fn rbtree_matches_btreeset(ops in prop::collection::vec(op(), 1..300)) {
+        check_against_model(RBTree::new(), &ops, rb_max_height)?;
+    }
+
+    #[test]
+    fn arena_avltree_matches_btreeset(ops in prop::collection::vec(op(), 1..300)) {
+        check_against_model(ArenaAVLTree::new(), &ops, avl_max_height)?;
+    }
+
+    #[test]
+    fn arena_rbtree_matches_btreeset(ops in prop::collection::vec(op(), 1..300)) {
+        check_against_model(ArenaRBTree::new(), &ops, rb_max_height)?;
+    }
+
+    #[test]
+    fn queries_match_btreeset(keys in prop::collection::vec(0..500u16, 0..200), probes in prop::collection::vec(0..520u16, 1..20)) {
+        let avl: AVLTree<u16> = keys.iter().copied().collect();
+        let rb: RBTree<u16> = keys.iter().copied().collect();
+        let model: BTreeSet<u16> = keys.iter().copied().collect();
+        let sorted: Vec<u16> = model.iter().copied().collect();
+
+        prop_assert_eq!(avl.iter().collect::<Vec<_>>(), sorted.clone());
+        prop_assert_eq!(rb.iter().rev().collect::<Vec<_>>(), sorted.iter().rev().copied().collect::<Vec<_>>());
+        for &probe in &probes {
+            let below = model.range(..probe).count();
+            prop_assert_eq!(avl.rank(&probe), below);
+            prop_assert_eq!(rb.rank(&probe), below);
+            prop_assert_eq!(avl.select(probe as usize), sorted.get(probe as usize).copied());
+            prop_assert_eq!(rb.select(probe as usize), sorted.get(probe as usize).copied());
+
+            let floor = model.range(..=probe).next_back().copied();
+            let successor = model.range(probe + 1..).next().copied();
+            prop_assert_eq!(avl.floor(&probe), floor);
+            prop_assert_eq!(rb.floor(&probe), floor);
+            prop_assert_eq!(avl.successor(&probe), successor);
+            prop_assert_eq!(rb.successor(&probe), successor);
+            let ceiling = model.range(probe..).next().copied();
+            let predecessor = model.range(..probe).next_back().copied();
+            prop_assert_eq!(avl.ceiling(&probe), ceiling);
+            prop_assert_eq!(rb.ceiling(&probe), ceiling);
+            prop_assert_eq!(avl.predecessor(&probe), predecessor);
+            prop_assert_eq!(rb.predecessor(&probe), predecessor);
+        }
+    }
+
+    #[test]
+    fn ranges_match_btreeset(
+        keys in prop::collection::vec(0..500u16, 0..200),
+        ranges in prop::collection::vec(bounds(), 1..10),
+        from_back in prop::collection::vec(any::<bool>(), 1..8),
+    ) {
+        let avl: AVLTree<u16> = keys.iter().copied().collect();
+        let rb: RBTree<u16> = keys.iter().copied().collect();
+        let arena_avl: ArenaAVLTree<u16> = keys.iter().copied().collect();
+        let arena_rb: ArenaRBTree<u16> = keys.iter().copied().collect();
+        let model: BTreeSet<u16> = keys.iter().copied().collect();
+
+        for &range in &ranges {
+            walk_range(avl.range(range), &model, range, &from_back)?;
+            walk_range(rb.range(range), &model, range, &from_back)?;
+            walk_range(arena_avl.range(range), &model, range, &from_back)?;
+            walk_range(arena_rb.range(range), &model, range, &from_back)?;
+
+            let count = model.range(range).count();
+            prop_assert_eq!(avl.count_range(range), count);
+            prop_assert_eq!(rb.count_range(range), count);
+            prop_assert_eq!(arena_avl.count_range(range), count);
+            prop_assert_eq!(arena_rb.count_range(range), count);
+        }
+    }
+
+    #[test]
+    fn split_and_append_match_btreeset(keys in prop::collection::vec(0..500u16, 0..200), at in 0..520u16) {
+        let mut avl: AVLTree<u16> = keys.iter().copied().collect();
+        let mut rb: RBTree<u16> = keys.iter().copied().collect();
+        let mut model: BTreeSet<u16> = keys.iter().copied().collect();
+        let model_high = model.split_off(&at);
+
+        let mut avl_high = avl.split_off(&at);
+        let mut rb_high = rb.split_off(&at);
+        for tree in [&avl, &avl_high] {
+            prop_assert!(tree.validate().is_ok());
+        }
+        for tree in [&rb, &rb_high] {
+            prop_assert!(tree.validate().is_ok());
+        }
+        prop_assert_eq!(avl.iter().collect::<BTreeSet<_>>(), model.clone());
+        prop_assert_eq!(rb_high.iter().collect::<BTreeSet<_>>(), model_high.clone());
+
+        avl.append(&mut avl_high);
+        rb.append(&mut rb_high);
+        model.extend(model_high);
+        prop_assert!(avl.validate().is_ok() && rb.validate().is_ok());
+        prop_assert_eq!(avl.iter().collect::<BTreeSet<_>>(), model.clone());
+        prop_assert_eq!(rb.iter().collect::<BTreeSet<_>>(), model);
+    }
+
+    // split_and_append_match_btreeset only appends trees whose keys don't
+    // interleave; here the two inputs overlap and share keys
+    #[test]
+    fn append_merges_overlapping_trees(a in prop::collection::vec(0..300u16, 0..150), b in prop::collection::vec(0..300u16, 0..150)) {
+        let model: Vec<u16> = a.iter().chain(&b).copied().collect::<BTreeSet<_>>().into_iter().collect();
+
+        let (mut avl, mut avl_b): (AVLTree<u16>, AVLTree<u16>) = (a.iter().copied().collect(), b.iter().copied().collect());
+        avl.append(&mut avl_b);
+        prop_assert!(avl.validate().is_ok());
+        prop_assert_eq!(avl.iter().collect::<Vec<_>>(), model.clone());
+        prop_assert_eq!(avl_b.iter().next(), None);
+
+        let (mut rb, mut rb_b): (RBTree<u16>, RBTree<u16>) = (a.iter().copied().collect(), b.iter().copied().collect());
+        rb.append(&mut rb_b);
+        prop_assert!(rb.validate().is_ok());
+        prop_assert_eq!(rb.iter().collect::<Vec<_>>(), model.clone());
+        prop_assert_eq!(rb_b.iter().next(), None);
+
+        let (mut arena_avl, mut arena_avl_b): (ArenaAVLTree<u16>, ArenaAVLTree<u16>) = (a.iter().copied().collect(), b.iter().copied().collect());
+        arena_avl.append(&mut arena_avl_b);
+        prop_assert!(arena_avl.validate().is_ok());
+        prop_assert_eq!(arena_avl.iter().collect::<Vec<_>>(), model.clone());
+        prop_assert_eq!(arena_avl_b.iter().next(), None);
+
+        let (mut arena_rb, mut arena_rb_b): (ArenaRBTree<u16>, ArenaRBTree<u16>) = (a.iter().copied().collect(), b.iter().copied().collect());
+        arena_rb.append(&mut arena_rb_b);
+        prop_assert!(arena_rb.validate().is_ok());
+        prop_assert_eq!(arena_rb.iter().collect::<Vec<_>>(), model);
+        prop_assert_eq!(arena_rb_b.iter().next(), None);
+    }
+
+    #[test]
+    fn set_algebra_matches_btreeset(a in prop::collection::vec(0..60u16, 0..40), b in prop::collection::vec(0..60u16, 0..40)) {
+        let (model_a, model_b): (BTreeSet<u16>, BTreeSet<u16>) = (a.iter().copied().collect(), b.iter().copied().collect());
+        let union: Vec<u16> = model_a.union(&model_b).copied().collect();
+        let intersection: Vec<u16> = model_a.intersection(&model_b).copied().collect();
+        let difference: Vec<u16> = model_a.difference(&model_b).copied().collect();
+        let symmetric: Vec<u16> = model_a.symmetric_difference(&model_b).copied().collect();
+
+        let (avl_a, avl_b): (AVLTree<u16>, AVLTree<u16>) = (a.iter().copied().collect(), b.iter().copied().collect());
+        prop_assert_eq!(avl_a.union(&avl_b).collect::<Vec<_>>(), union.clone());
+        prop_assert_eq!(avl_a.intersection(&avl_b).collect::<Vec<_>>(), intersection.clone());
+        prop_assert_eq!(avl_a.difference(&avl_b).collect::<Vec<_>>(), difference.clone());
+        prop_assert_eq!(avl_a.symmetric_difference(&avl_b).collect::<Vec<_>>(), symmetric.clone());
+        for (tree, expected) in [
+            (avl_a.union_tree(&avl_b), &union),
+            (avl_a.intersection_tree(&avl_b), &intersection),
+            (avl_a.difference_tree(&avl_b), &difference),
+            (avl_a.symmetric_difference_tree(&avl_b), &symmetric),
+        ] {
+            prop_assert!(tree.validate().is_ok());
+            prop_assert_eq!(&tree.iter().collect::<Vec<_>>(), expected);
+        }
+        prop_assert_eq!(avl_a.is_subset(&avl_b), model_a.is_subset(&model_b));
+        prop_assert_eq!(avl_a.is_superset(&avl_b), model_a.is_superset(&model_b));
+        prop_assert_eq!(avl_a.is_disjoint(&avl_b), model_a.is_disjoint(&model_b));
+
+        let (rb_a, rb_b): (RBTree<u16>, RBTree<u16>) = (a.iter().copied().collect(), b.iter().copied().collect());
+        prop_assert_eq!(rb_a.union(&rb_b).collect::<Vec<_>>(), union.clone());
+        prop_assert_eq!(rb_a.intersection(&rb_b).collect::<Vec<_>>(), intersection.clone());
+        prop_assert_eq!(rb_a.difference(&rb_b).collect::<Vec<_>>(), difference.clone());
+        prop_assert_eq!(rb_a.symmetric_difference(&rb_b).collect::<Vec<_>>(), symmetric.clone());
+        for (tree, expected) in [
+            (rb_a.union_tree(&rb_b), &union),
+            (rb_a.intersection_tree(&rb_b), &intersection),
+            (rb_a.difference_tree(&rb_b), &difference),
+            (rb_a.symmetric_difference_tree(&rb_b), &symmetric),
+        ] {
+            prop_assert!(tree.validate().is_ok());
+            prop_assert_eq!(&tree.iter().collect::<Vec<_>>(), expected);
+        }
+        prop_assert_eq!(rb_a.is_subset(&rb_b), model_a.is_subset(&model_b));
+        prop_assert_eq!(rb_a.is_superset(&rb_b), model_a.is_superset(&model_b));
+        prop_assert_eq!(rb_a.is_disjoint(&rb_b), model_a.is_disjoint(&model_b));
+        // random sets are rarely nested, so check a pair that always is
+        let (avl_common, rb_common) = (avl_a.intersection_tree(&avl_b), rb_a.intersection_tree(&rb_b));
+        prop_assert!(avl_common.is_subset(&avl_a) && avl_b.is_superset(&avl_common));
+        prop_assert!(rb_common.is_subset(&rb_b) && rb_a.is_superset(&rb_common));
+    }
+
+    #[test]
+    fn arena_queries_match_btreeset(keys in prop::collection::vec(0..500u16, 0..200), probe in 0..520u16) {
+        let mut avl: ArenaAVLTree<u16> = keys.iter().copied().collect();
+        let mut rb: ArenaRBTree<u16> = keys.iter().copied().collect();
+        let mut model: BTreeSet<u16> = keys.iter().copied().collect();
+        let sorted: Vec<u16> = model.iter().copied().collect();
+
+        prop_assert_eq!(avl.iter().rev().collect::<Vec<_>>(), sorted.iter().rev().copied().collect::<Vec<_>>());
+        prop_assert_eq!(rb.iter().collect::<Vec<_>>(), sorted.clone());
+        prop_assert_eq!(avl.rank(&probe), model.range(..probe).count());
+        prop_assert_eq!(rb.select(probe as usize), sorted.get(probe as usize).copied());
+        prop_assert_eq!(avl.ceiling(&probe), model.range(probe..).next().copied());
+        prop_assert_eq!(rb.predecessor(&probe), model.range(..probe).next_back().copied());
+        prop_assert_eq!(rb.count_range(probe..probe + 50), model.range(probe..probe + 50).count());
+
+        let model_high = model.split_off(&probe);
+        let mut avl_high = avl.split_off(&probe);
+        let mut rb_high = rb.split_off(&probe);
+        prop_assert!(avl.validate().is_ok() && avl_high.validate().is_ok());
+        prop_assert!(rb.validate().is_ok() && rb_high.validate().is_ok());
+        prop_assert_eq!(avl.iter().collect::<BTreeSet<_>>(), model.clone());
+        prop_assert_eq!(rb_high.iter().collect::<BTreeSet<_>>(), model_high.clone());
+
+        avl.append(&mut avl_high);
+        rb.append(&mut rb_high);
+        model.extend(model_high);
+        prop_assert!(avl.validate().is_ok() && rb.validate().is_ok());
+        prop_assert_eq!(avl.into_iter().collect::<Vec<_>>(), sorted.clone());
+        prop_assert_eq!(rb.into_iter().collect::<Vec<_>>(), sorted);
+    }
+}
+
+// keys that implement neither `Display` nor `Debug` work through the whole
+// arena API, `OrderedSet` included
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct Opaque(u16);
+
+#[test]
+fn arena_trees_take_keys_that_cannot_print() {
+    let mut avl: ArenaAVLTree<Opaque> = (0..50).map(Opaque).collect();
+    let mut rb = ArenaRBTree::default();
+    rb.extend((0..50).map(Opaque));
+    assert!(avl.search(&Opaque(7)) && rb.search(&Opaque(7)).is_some());
+    assert!(avl.delete(&Opaque(7)).is_ok() && rb.delete(&Opaque(7)).is_ok());
+    assert!(OrderedSet::remove(&mut avl, &Opaque(8)) && OrderedSet::remove(&mut rb, &Opaque(8)));
+    assert_eq!(OrderedSet::len(&avl), 48);
+    assert_eq!(OrderedSet::len(&rb), 48);
+    let avl_keys: Vec<u16> = (&avl).into_iter().map(|Opaque(key)| key).collect();
+    let rb_keys: Vec<u16> = rb.iter().map(|Opaque(key)| key).collect();
+    assert_eq!(avl_keys, rb_keys);
+    assert!(avl.validate().is_ok() && rb.validate().is_ok());
+}
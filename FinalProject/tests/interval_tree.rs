@@ -0,0 +1,98 @@
+// Interval queries checked against a brute-force scan, with the cached
+// endpoints validated after every insert and remove.
+
+use proptest::prelude::*;
+use FinalProject::error::TreeError;
+use FinalProject::interval_tree::{Interval, IntervalTree};
+
+#[derive(Clone, Debug)]
+enum Op {
+    Insert(u16, u16),
+    Remove(u16, u16),
+}
+
+fn interval() -> impl Strategy<Value = (u16, u16)> {
+    (0..100u16, 0..20u16).prop_map(|(start, len)| (start, start + len))
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        3 => interval().prop_map(|(start, end)| Op::Insert(start, end)),
+        2 => interval().prop_map(|(start, end)| Op::Remove(start, end)),
+    ]
+}
+
+fn pairs<T>(intervals: Vec<Interval<T>>) -> Vec<(T, T)> {
+    intervals.into_iter().map(|i| (i.start, i.end)).collect()
+}
+
+#[test]
+fn meeting_rooms() {
+    let mut rooms: IntervalTree<u32> = [(9, 10), (13, 15), (10, 12), (9, 17), (16, 18)].into_iter().collect();
+    assert!(rooms.validate().is_ok());
+    assert_eq!(pairs(rooms.containing(10)), [(9, 10), (9, 17), (10, 12)]);
+    assert_eq!(rooms.stabbing_count(12), 2);
+    assert_eq!(rooms.stabbing_count(8), 0);
+    assert_eq!(pairs(rooms.overlapping(15, 16)), [(9, 17), (13, 15), (16, 18)]);
+
+    assert_eq!(rooms.insert(10, 12), Ok(false));
+    assert!(rooms.remove(9, 17));
+    assert!(!rooms.remove(9, 17));
+    assert!(rooms.validate().is_ok());
+    assert_eq!(rooms.stabbing_count(12), 1);
+    assert!(rooms.overlapping(12, 13).iter().all(|i| i.overlaps(&12, &13)));
+}
+
+#[test]
+fn reversed_intervals_are_rejected() {
+    let mut tree: IntervalTree<i32> = [(1, 4), (6, 6)].into_iter().collect();
+    assert_eq!(tree.insert(5, 2), Err(TreeError::InvalidInterval));
+    assert_eq!(tree.len(), 2);
+    assert!(!tree.contains(5, 2) && !tree.contains(2, 5));
+    assert!(tree.validate().is_ok());
+    assert_eq!(tree.insert(3, 3), Ok(true));
+    assert_eq!(TreeError::InvalidInterval.to_string(), "interval start is after its end");
+}
+
+#[test]
+#[should_panic(expected = "interval start is after its end")]
+fn collecting_a_reversed_interval_panics() {
+    let _: IntervalTree<i32> = [(1, 4), (9, 2)].into_iter().collect();
+}
+
+proptest! {
+    #[test]
+    fn queries_match_a_scan(ops in prop::collection::vec(op(), 1..200), queries in prop::collection::vec(interval(), 10)) {
+        let mut tree = IntervalTree::new();
+        let mut model: Vec<(u16, u16)> = Vec::new();
+        for op in &ops {
+            match *op {
+                Op::Insert(start, end) => {
+                    let fresh = !model.contains(&(start, end));
+                    if fresh {
+                        model.push((start, end));
+                    }
+                    prop_assert_eq!(tree.insert(start, end), Ok(fresh));
+                },
+                Op::Remove(start, end) => {
+                    let present = model.contains(&(start, end));
+                    model.retain(|&i| i != (start, end));
+                    prop_assert_eq!(tree.remove(start, end), present);
+                },
+            }
+            if let Err(violation) = tree.validate() {
+                return Err(TestCaseError::fail(format!("after {:?}: {}", op, violation)));
+            }
+        }
+        model.sort();
+        prop_assert_eq!(tree.len(), model.len());
+
+        for &(low, high) in &queries {
+            let expected: Vec<_> = model.iter().copied().filter(|&(s, e)| s <= high && e >= low).collect();
+            prop_assert_eq!(pairs(tree.overlapping(low, high)), expected);
+            let stabbed = model.iter().filter(|&&(s, e)| s <= low && low <= e).count();
+            prop_assert_eq!(tree.stabbing_count(low), stabbed);
+            prop_assert_eq!(tree.containing(low).len(), stabbed);
+        }
+    }
+}
@@ -0,0 +1,134 @@
+// The entry API of both maps: counters and upserts, checked against
+// `BTreeMap::entry` for random workloads.
+
+use std::cell::Cell;
+use std::collections::BTreeMap;
+
+use proptest::prelude::*;
+use FinalProject::avlmap::{self, AVLMap};
+use FinalProject::rbmap::{self, RBMap};
+
+const TEXT: &str = "the quick brown fox jumps over the lazy dog the fox";
+
+#[derive(Clone, Debug)]
+enum Op {
+    Add(u8, i32),
+    Take(u8),
+}
+
+thread_local! {
+    static CLONES: Cell<usize> = const { Cell::new(0) };
+}
+
+// key that counts how often it is cloned
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Tracked(u32);
+
+impl Clone for Tracked {
+    fn clone(&self) -> Self {
+        CLONES.with(|clones| clones.set(clones.get() + 1));
+        Tracked(self.0)
+    }
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        3 => (0..60u8, -5..5i32).prop_map(|(key, delta)| Op::Add(key, delta)),
+        1 => (0..60u8).prop_map(Op::Take),
+    ]
+}
+
+#[test]
+fn word_counts() {
+    let mut rb: RBMap<&str, usize> = RBMap::new();
+    let mut avl: AVLMap<String, usize> = AVLMap::new();
+    for word in TEXT.split(' ') {
+        *rb.entry(word).or_insert(0) += 1;
+        avl.entry(word.to_string()).and_modify(|n| *n += 1).or_insert(1);
+    }
+    assert_eq!(rb.get(&"the"), Some(&3));
+    assert_eq!(avl.get(&"fox".to_string()), Some(&2));
+    assert_eq!(rb.len(), 8);
+    assert_eq!(avl.len(), 8);
+
+    match rb.entry("dog") {
+        rbmap::Entry::Occupied(entry) => {
+            assert_eq!(entry.key(), &"dog");
+            assert_eq!(entry.remove_entry(), ("dog", 1));
+        },
+        rbmap::Entry::Vacant(_) => panic!("dog was counted"),
+    }
+    assert!(!rb.contains_key(&"dog"));
+    match avl.entry("cat".to_string()) {
+        avlmap::Entry::Vacant(entry) => assert_eq!(entry.into_key(), "cat"),
+        avlmap::Entry::Occupied(_) => panic!("cat was never counted"),
+    }
+    assert!(!avl.contains_key(&"cat".to_string()));
+    assert_eq!(*avl.entry("cat".to_string()).or_insert_with_key(|key| key.len()), 3);
+    assert_eq!(avl.insert("cat".to_string(), 9), Some(3));
+}
+
+#[test]
+fn lookups_and_removals_leave_keys_alone() {
+    let mut rb: RBMap<Tracked, u32> = RBMap::new();
+    let mut avl: AVLMap<Tracked, u32> = AVLMap::new();
+    for i in 0..100 {
+        rb.insert(Tracked(i), i);
+        avl.insert(Tracked(i), i);
+    }
+    for i in 0..100 {
+        assert_eq!(rb.get(&Tracked(i)), Some(&i));
+        assert!(avl.contains_key(&Tracked(i)));
+        *avl.get_mut(&Tracked(i)).unwrap() += 1;
+    }
+    for i in (0..100).step_by(2) {
+        assert_eq!(rb.remove(&Tracked(i)), Some(i));
+        match avl.entry(Tracked(i)) {
+            avlmap::Entry::Occupied(entry) => assert_eq!(entry.remove_entry(), (Tracked(i), i + 1)),
+            avlmap::Entry::Vacant(_) => panic!("{i} was inserted"),
+        }
+    }
+    assert_eq!(CLONES.with(Cell::get), 0);
+    assert_eq!((rb.len(), avl.len()), (50, 50));
+}
+
+proptest! {
+    #[test]
+    fn entries_match_btreemap(ops in prop::collection::vec(op(), 1..300)) {
+        let mut rb: RBMap<u8, i32> = RBMap::new();
+        let mut avl: AVLMap<u8, i32> = AVLMap::new();
+        let mut model = BTreeMap::new();
+        for op in &ops {
+            match *op {
+                Op::Add(key, delta) => {
+                    *rb.entry(key).or_default() += delta;
+                    *avl.entry(key).or_default() += delta;
+                    *model.entry(key).or_default() += delta;
+                },
+                Op::Take(key) => {
+                    let expected = match model.entry(key) {
+                        std::collections::btree_map::Entry::Occupied(entry) => Some(entry.remove()),
+                        std::collections::btree_map::Entry::Vacant(_) => None,
+                    };
+                    let taken = match rb.entry(key) {
+                        rbmap::Entry::Occupied(entry) => Some(entry.remove()),
+                        rbmap::Entry::Vacant(_) => None,
+                    };
+                    prop_assert_eq!(taken, expected);
+                    let taken = match avl.entry(key) {
+                        avlmap::Entry::Occupied(entry) => Some(entry.remove()),
+                        avlmap::Entry::Vacant(_) => None,
+                    };
+                    prop_assert_eq!(taken, expected);
+                },
+            }
+        }
+        let keys: Vec<u8> = model.keys().copied().collect();
+        prop_assert_eq!(rb.keys(), keys.clone());
+        prop_assert_eq!(avl.keys(), keys);
+        for (key, value) in &model {
+            prop_assert_eq!(rb.get(key), Some(value));
+            prop_assert_eq!(avl.get(key), Some(value));
+        }
+    }
+}
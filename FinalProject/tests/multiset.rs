@@ -0,0 +1,115 @@
+// Bag semantics on both trees: duplicates are counted per key and iteration
+// yields every copy.
+
+use std::cell::Cell;
+
+use FinalProject::avlmultiset::AVLMultiset;
+use FinalProject::rbmultiset::RBMultiset;
+
+// event timestamps with repeats
+const STAMPS: [u32; 10] = [30, 10, 20, 10, 30, 30, 40, 10, 20, 50];
+
+thread_local! {
+    static CLONES: Cell<usize> = const { Cell::new(0) };
+}
+
+// key that counts how often it is cloned
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Tracked(u32);
+
+impl Clone for Tracked {
+    fn clone(&self) -> Self {
+        CLONES.with(|clones| clones.set(clones.get() + 1));
+        Tracked(self.0)
+    }
+}
+
+#[test]
+fn duplicates_are_counted() {
+    let mut rb: RBMultiset<u32> = STAMPS.iter().copied().collect();
+    let mut avl: AVLMultiset<u32> = STAMPS.iter().copied().collect();
+    for (len, distinct) in [(rb.len(), rb.distinct_len()), (avl.len(), avl.distinct_len())] {
+        assert_eq!(len, 10);
+        assert_eq!(distinct, 5);
+    }
+    assert_eq!(rb.count_of(&10), 3);
+    assert_eq!(avl.count_of(&30), 3);
+    assert_eq!(rb.count_of(&15), 0);
+    assert_eq!(rb.insert(50), 2);
+    assert_eq!(avl.insert(60), 1);
+
+    assert!(rb.remove_one(&10));
+    assert!(avl.remove_one(&10));
+    assert_eq!(rb.count_of(&10), 2);
+    assert_eq!(avl.count_of(&10), 2);
+    assert!(rb.remove_one(&40));
+    assert!(!rb.contains(&40));
+    assert!(!rb.remove_one(&40));
+
+    assert_eq!(rb.remove_all(&30), 3);
+    assert_eq!(avl.remove_all(&30), 3);
+    assert_eq!(rb.remove_all(&30), 0);
+    assert_eq!(rb.len(), 6);
+    assert_eq!(avl.len(), 7);
+    assert_eq!(rb.distinct_len(), 3);
+}
+
+#[test]
+fn iteration_yields_every_copy() {
+    let rb: RBMultiset<u32> = STAMPS.iter().copied().collect();
+    let avl: AVLMultiset<u32> = STAMPS.iter().copied().collect();
+    let mut sorted = STAMPS.to_vec();
+    sorted.sort();
+    assert_eq!(rb.iter().collect::<Vec<_>>(), sorted);
+    assert_eq!(avl.iter().collect::<Vec<_>>(), sorted);
+    sorted.reverse();
+    assert_eq!(rb.iter().rev().collect::<Vec<_>>(), sorted);
+    assert_eq!(avl.iter().rev().collect::<Vec<_>>(), sorted);
+}
+
+#[test]
+fn both_ends_share_the_last_key() {
+    let mut rb = RBMultiset::new();
+    let mut avl = AVLMultiset::new();
+    for _ in 0..4 {
+        rb.insert("tick");
+        avl.insert("tick");
+    }
+    rb.insert("tock");
+    avl.insert("tock");
+
+    let mut iter = rb.iter();
+    assert_eq!(iter.next(), Some("tick"));
+    assert_eq!(iter.next_back(), Some("tock"));
+    assert_eq!(iter.next_back(), Some("tick"));
+    assert_eq!(iter.next(), Some("tick"));
+    assert_eq!(iter.next_back(), Some("tick"));
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+
+    let mut iter = avl.iter();
+    assert_eq!(iter.next_back(), Some("tock"));
+    assert_eq!(iter.next_back(), Some("tick"));
+    assert_eq!(iter.next(), Some("tick"));
+    assert_eq!(iter.by_ref().count(), 2);
+    assert_eq!(iter.next_back(), None);
+}
+
+#[test]
+fn counting_leaves_keys_alone() {
+    let mut rb = RBMultiset::new();
+    let mut avl = AVLMultiset::new();
+    for i in 0..200 {
+        rb.insert(Tracked(i % 50));
+        avl.insert(Tracked(i % 50));
+    }
+    for i in 0..50 {
+        assert_eq!(rb.count_of(&Tracked(i)), 4);
+        assert!(avl.contains(&Tracked(i)));
+        assert!(rb.remove_one(&Tracked(i)));
+        assert_eq!(avl.remove_all(&Tracked(i)), 4);
+    }
+    assert_eq!(CLONES.with(Cell::get), 0);
+    assert_eq!((rb.len(), rb.distinct_len()), (150, 50));
+    assert!(avl.is_empty());
+}
@@ -0,0 +1,125 @@
+// Popping from both ends of the trees and of the priority deques built on
+// them, checked against a sorted vector.
+
+use proptest::prelude::*;
+use FinalProject::avltree::AVLTree;
+use FinalProject::ordered_set::OrderedSet;
+use FinalProject::priority_deque::{AVLPriorityDeque, RBPriorityDeque};
+use FinalProject::rbtree::RBTree;
+
+#[derive(Clone, Debug)]
+enum Op {
+    Push(u8),
+    PopMin,
+    PopMax,
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        3 => (0..50u8).prop_map(Op::Push),
+        1 => Just(Op::PopMin),
+        1 => Just(Op::PopMax),
+    ]
+}
+
+#[test]
+fn trees_pop_both_ends() {
+    let mut rb: RBTree<u32> = (1..=20).collect();
+    let mut avl: AVLTree<u32> = (1..=20).collect();
+    assert_eq!((rb.first(), rb.last()), (Some(1), Some(20)));
+    assert_eq!((avl.first(), avl.last()), (Some(1), Some(20)));
+    for i in 1..=5 {
+        assert_eq!(rb.pop_first(), Some(i));
+        assert_eq!(avl.pop_last(), Some(21 - i));
+    }
+    assert!(rb.validate().is_ok());
+    assert!(avl.validate().is_ok());
+    assert_eq!(rb.count(), 15);
+    assert_eq!(avl.first(), Some(1));
+
+    // a node held through a search handle still comes out
+    let handle = rb.search(&20);
+    assert_eq!(rb.pop_last(), Some(20));
+    assert!(handle.is_some());
+    assert!(rb.validate().is_ok());
+    assert_eq!(rb.last(), Some(19));
+
+    let mut sets: Vec<Box<dyn OrderedSet<u32>>> = vec![Box::new(rb), Box::new(avl)];
+    for set in &mut sets {
+        while set.pop_first().is_some() {}
+        assert!(set.is_empty());
+        assert_eq!(set.pop_last(), None);
+    }
+}
+
+#[test]
+fn equal_priorities_are_all_kept() {
+    let mut deque: RBPriorityDeque<u32> = [5, 1, 5, 3, 5, 1].into_iter().collect();
+    assert_eq!(deque.len(), 6);
+    assert_eq!((deque.peek_min(), deque.peek_max()), (Some(1), Some(5)));
+    assert_eq!(deque.pop_max(), Some(5));
+    assert_eq!(deque.pop_min(), Some(1));
+    assert_eq!(deque.pop_min(), Some(1));
+    assert_eq!(deque.pop_min(), Some(3));
+    assert_eq!(deque.len(), 2);
+}
+
+// payloads that can't be printed: a job type with neither `Display` nor
+// `Debug`, and a tuple holding a `String`
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct Job {
+    priority: u32,
+    name: String,
+}
+
+#[test]
+fn payloads_need_not_print() {
+    let mut jobs: AVLPriorityDeque<Job> = AVLPriorityDeque::new();
+    for (priority, name) in [(2, "build"), (9, "deploy"), (1, "lint"), (2, "test")] {
+        jobs.push(Job { priority, name: name.to_string() });
+    }
+    let name = |job: Option<Job>| job.map(|job| job.name);
+    assert_eq!(name(jobs.peek_max()), Some("deploy".to_string()));
+    assert_eq!(name(jobs.pop_min()), Some("lint".to_string()));
+    assert_eq!(name(jobs.pop_min()), Some("build".to_string()));
+    assert_eq!(name(jobs.pop_max()), Some("deploy".to_string()));
+    assert_eq!(jobs.len(), 1);
+
+    let mut tasks: RBPriorityDeque<(u32, String)> =
+        [(3, "c"), (1, "a"), (2, "b")].into_iter().map(|(p, s)| (p, s.to_string())).collect();
+    assert_eq!(tasks.pop_max(), Some((3, "c".to_string())));
+    assert_eq!(tasks.pop_min(), Some((1, "a".to_string())));
+    assert_eq!(tasks.len(), 1);
+}
+
+proptest! {
+    #[test]
+    fn deques_match_a_sorted_vec(ops in prop::collection::vec(op(), 1..300)) {
+        let mut rb = RBPriorityDeque::new();
+        let mut avl = AVLPriorityDeque::new();
+        let mut model: Vec<u8> = Vec::new();
+        for op in &ops {
+            match *op {
+                Op::Push(item) => {
+                    rb.push(item);
+                    avl.push(item);
+                    let at = model.partition_point(|&i| i <= item);
+                    model.insert(at, item);
+                },
+                Op::PopMin => {
+                    let expected = if model.is_empty() { None } else { Some(model.remove(0)) };
+                    prop_assert_eq!(rb.pop_min(), expected);
+                    prop_assert_eq!(avl.pop_min(), expected);
+                },
+                Op::PopMax => {
+                    let expected = model.pop();
+                    prop_assert_eq!(rb.pop_max(), expected);
+                    prop_assert_eq!(avl.pop_max(), expected);
+                },
+            }
+            prop_assert_eq!(rb.len(), model.len());
+            prop_assert_eq!(avl.peek_min(), model.first().copied());
+            prop_assert_eq!(rb.peek_max(), model.last().copied());
+        }
+    }
+}
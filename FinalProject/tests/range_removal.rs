@@ -0,0 +1,85 @@
+// Bulk removal from both trees: retain and drain rebuild the tree, range
+// removal splits and rejoins it, so every one is followed by a full
+// invariant check.
+
+use std::collections::BTreeSet;
+use std::ops::Bound;
+
+use proptest::prelude::*;
+use FinalProject::avltree::AVLTree;
+use FinalProject::rbtree::RBTree;
+
+fn bound() -> impl Strategy<Value = Bound<u16>> {
+    prop_oneof![
+        (0..300u16).prop_map(Bound::Included),
+        (0..300u16).prop_map(Bound::Excluded),
+        Just(Bound::Unbounded),
+    ]
+}
+
+#[test]
+fn purge_expired_keys() {
+    // expiry timestamps; everything up to `now` has expired
+    let now = 500;
+    let mut rb: RBTree<u32> = (0..1000).map(|i| i * 7 % 1000).collect();
+    let mut avl: AVLTree<u32> = (0..1000).collect();
+
+    assert_eq!(rb.remove_range(..=now), 501);
+    assert_eq!(avl.drain_range(..=now).collect::<Vec<_>>(), (0..=now).collect::<Vec<_>>());
+    for (first, count) in [(rb.first(), rb.count()), (avl.first(), avl.count())] {
+        assert_eq!(first, Some(now + 1));
+        assert_eq!(count, 499);
+    }
+    assert!(rb.validate().is_ok());
+    assert!(avl.validate().is_ok());
+
+    rb.retain(|key| key % 2 == 0);
+    avl.retain(|value| value % 2 == 0);
+    assert!(rb.validate().is_ok());
+    assert!(avl.validate().is_ok());
+    assert_eq!(rb.iter().collect::<Vec<_>>(), avl.iter().collect::<Vec<_>>());
+    assert_eq!(rb.count(), 249);
+
+    assert_eq!(rb.drain().count(), 249);
+    assert!(rb.is_empty());
+    assert!(rb.insert(3));
+    assert_eq!(avl.drain().next(), Some(502));
+    assert!(avl.is_empty());
+}
+
+#[test]
+fn descending_trees_use_their_own_order() {
+    let mut rb = RBTree::with_comparator(|a: &u32, b: &u32| b.cmp(a));
+    rb.extend(0..20);
+    // in descending order the range 15..=5 runs from 15 down to 5
+    assert_eq!(rb.drain_range((Bound::Included(15), Bound::Included(5))).collect::<Vec<_>>(), (5..=15).rev().collect::<Vec<_>>());
+    assert!(rb.validate().is_ok());
+    assert_eq!(rb.count(), 9);
+}
+
+proptest! {
+    #[test]
+    fn removals_match_btreeset(keys in prop::collection::btree_set(0..300u16, 0..200), lower in bound(), upper in bound(), modulus in 1..5u16) {
+        let mut model: BTreeSet<u16> = keys;
+        let mut rb: RBTree<u16> = model.iter().copied().collect();
+        let mut avl: AVLTree<u16> = model.iter().copied().collect();
+
+        let range = (lower, upper);
+        let in_range = |key: &u16| std::ops::RangeBounds::contains(&range, key);
+        let expected: Vec<u16> = model.iter().copied().filter(in_range).collect();
+        model.retain(|key| !in_range(key));
+        prop_assert_eq!(rb.drain_range(range).collect::<Vec<_>>(), expected.clone());
+        prop_assert_eq!(avl.remove_range(range), expected.len());
+        prop_assert!(rb.validate().is_ok());
+        prop_assert!(avl.validate().is_ok());
+
+        model.retain(|key| key % modulus != 0);
+        rb.retain(|key| key % modulus != 0);
+        avl.retain(|key| key % modulus != 0);
+        prop_assert!(rb.validate().is_ok());
+        prop_assert!(avl.validate().is_ok());
+        let remaining: Vec<u16> = model.into_iter().collect();
+        prop_assert_eq!(rb.iter().collect::<Vec<_>>(), remaining.clone());
+        prop_assert_eq!(avl.iter().collect::<Vec<_>>(), remaining);
+    }
+}
@@ -0,0 +1,46 @@
+// The delete trace hook sees every deletion step, and deletes without a hook
+// installed behave the same.
+
+use std::cell::RefCell;
+
+use FinalProject::rbtree::RBTree;
+
+thread_local! {
+    static STEPS: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+}
+
+fn record(tree: &RBTree<u32>, step: &'static str) {
+    assert!(tree.count() < 200);
+    STEPS.with(|steps| steps.borrow_mut().push(step));
+}
+
+#[test]
+fn hook_sees_each_deletion() {
+    let mut traced: RBTree<u32> = (0..200).collect();
+    let mut silent: RBTree<u32> = (0..200).collect();
+    traced.set_delete_trace(Some(record));
+    for key in 0..150 {
+        assert_eq!(traced.delete(&key), silent.delete(&key));
+    }
+    assert_eq!(traced.inorder_traversal(), silent.inorder_traversal());
+    assert!(traced.validate().is_ok());
+
+    let steps = STEPS.with(|steps| steps.take());
+    assert_eq!(steps.iter().filter(|&&step| step == "unlinked").count(), 150);
+    assert!(steps.len() > 150, "no fix-up step was reported");
+
+    traced.set_delete_trace(None);
+    traced.delete(&199).unwrap();
+    assert!(STEPS.with(|steps| steps.borrow().is_empty()));
+}
+
+#[test]
+fn hook_survives_extending_an_empty_tree() {
+    let mut tree: RBTree<u32> = RBTree::new();
+    tree.set_delete_trace(Some(record));
+    // sorted keys above the (missing) maximum are joined on through append
+    tree.extend(0..50);
+    assert_eq!(tree.count(), 50);
+    tree.delete(&10).unwrap();
+    assert!(STEPS.with(|steps| steps.take()).contains(&"unlinked"));
+}
@@ -0,0 +1,85 @@
+// Dropping an RBTree has to free its nodes. Weak handles to every node are
+// taken through `search`, and once the tree is gone none of them may still
+// upgrade.
+
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+use FinalProject::error::TreeError;
+use FinalProject::ordered_set::OrderedSet;
+use FinalProject::rbtree::{Node, RBTree};
+
+fn node_handles(tree: &RBTree<u32>) -> Vec<Weak<RefCell<Node<u32>>>> {
+    tree.iter()
+        .map(|key| Rc::downgrade(&tree.search(&key).expect("key from iter is in the tree")))
+        .collect()
+}
+
+fn live_nodes(handles: &[Weak<RefCell<Node<u32>>>]) -> usize {
+    handles.iter().filter(|handle| handle.strong_count() > 0).count()
+}
+
+#[test]
+fn dropping_tree_frees_all_nodes() {
+    let mut tree = RBTree::new();
+    for key in 0..1000 {
+        tree.insert((key * 7919) % 1000);
+    }
+    let handles = node_handles(&tree);
+    assert_eq!(live_nodes(&handles), 1000);
+
+    drop(tree);
+    assert_eq!(live_nodes(&handles), 0);
+}
+
+#[test]
+fn deleted_nodes_are_freed() {
+    let mut tree: RBTree<u32> = (0..500).collect();
+    let handles = node_handles(&tree);
+    for key in (0..500).step_by(2) {
+        assert_eq!(tree.delete(&key), Ok(key));
+    }
+    assert_eq!(live_nodes(&handles), 250);
+
+    drop(tree);
+    assert_eq!(live_nodes(&handles), 0);
+}
+
+#[test]
+fn split_and_append_do_not_leak() {
+    let mut low: RBTree<u32> = (0..400).collect();
+    let mut handles = node_handles(&low);
+    let mut high = low.split_off(&200);
+    let mut extra: RBTree<u32> = (400..600).collect();
+    handles.extend(node_handles(&extra));
+    high.append(&mut extra);
+    low.append(&mut high);
+    // append may rebuild the node joining the two trees, so count the nodes
+    // of the result rather than the ones taken before
+    let merged = node_handles(&low);
+    assert_eq!(live_nodes(&merged), 600);
+
+    drop((low, high, extra));
+    assert_eq!(live_nodes(&handles), 0);
+    assert_eq!(live_nodes(&merged), 0);
+}
+
+#[test]
+fn delete_clones_out_of_held_nodes() {
+    let mut tree: RBTree<u32> = (0..10).collect();
+    if let Some(_handle) = tree.search(&4) {
+        assert_eq!(tree.delete(&4), Ok(4));
+    }
+    let handles = [tree.search(&5).unwrap(), tree.search(&7).unwrap()];
+    assert_eq!(tree.delete(&5), Ok(5));
+    assert_eq!(tree.delete(&5), Err(TreeError::KeyNotFound));
+    assert!(OrderedSet::remove(&mut tree, &7));
+    assert!(!OrderedSet::remove(&mut tree, &7));
+    assert!(tree.validate().is_ok());
+    assert_eq!(tree.count(), 7);
+
+    // the handles keep their nodes, now unlinked, until dropped
+    let nodes: Vec<_> = handles.iter().map(Rc::downgrade).collect();
+    drop(handles);
+    assert_eq!(live_nodes(&nodes), 0);
+}
@@ -0,0 +1,60 @@
+// Keys that are neither `Copy` nor `Display`: lookups go through borrowed
+// forms, like `&str` for `String` keys, and nothing is printed on the way.
+
+use std::ops::Bound;
+
+use FinalProject::arena_rbtree::ArenaRBTree;
+use FinalProject::rbmap::RBMap;
+use FinalProject::rbtree::RBTree;
+
+const WORDS: [&str; 8] = ["pear", "fig", "apple", "plum", "kiwi", "lime", "date", "cherry"];
+
+#[test]
+fn string_keys_are_looked_up_by_str() {
+    let mut tree: RBTree<String> = RBTree::new();
+    for word in WORDS {
+        tree.insert(word.to_string());
+    }
+    assert!(tree.validate().is_ok());
+    assert!(tree.search_element("kiwi"));
+    assert!(!tree.search_element("mango"));
+    assert_eq!(tree.get("plum"), Some("plum".to_string()));
+    assert_eq!(tree.rank("fig"), 3);
+    assert_eq!(tree.floor("grape"), Some("fig".to_string()));
+    assert_eq!(tree.successor("plum"), None);
+    assert_eq!(tree.count_range::<str, _>((Bound::Included("d"), Bound::Excluded("l"))), 3);
+
+    assert_eq!(tree.delete("apple"), Ok("apple".to_string()));
+    assert!(tree.delete("lime").is_ok());
+    assert!(tree.validate().is_ok());
+    let high = tree.split_off("kiwi");
+    assert_eq!(tree.into_iter().collect::<Vec<_>>(), ["cherry", "date", "fig"]);
+    assert_eq!(high.iter().collect::<Vec<_>>(), ["kiwi", "pear", "plum"]);
+}
+
+#[test]
+fn keys_without_display_can_be_deleted() {
+    let mut tree: RBTree<Vec<u8>> = (0..100u8).map(|i| vec![i % 10, i]).collect();
+    for i in (0..100u8).step_by(3) {
+        assert!(tree.delete(&[i % 10, i][..]).is_ok());
+    }
+    assert!(tree.validate().is_ok());
+    assert_eq!(tree.count(), 66);
+
+    let mut arena: ArenaRBTree<Vec<u8>> = tree.iter().collect();
+    assert_eq!(arena.delete(&[1, 1][..]), Ok(vec![1, 1]));
+    assert!(arena.validate().is_ok());
+    assert_eq!(arena.min(), Some(vec![0, 10]));
+}
+
+#[test]
+fn map_with_string_keys() {
+    let mut map = RBMap::new();
+    for (i, word) in WORDS.iter().enumerate() {
+        map.insert(word.to_string(), i);
+    }
+    assert_eq!(map.get(&"lime".to_string()), Some(&5));
+    assert_eq!(map.remove(&"fig".to_string()), Some(1));
+    assert_eq!(map.len(), 7);
+    assert_eq!(map.keys().first().map(String::as_str), Some("apple"));
+}
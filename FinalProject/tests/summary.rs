@@ -0,0 +1,123 @@
+// Range aggregates of the summary trees checked against folding the keys of
+// a sorted model, with the cached summaries validated after every change.
+
+use std::ops::Bound;
+
+use proptest::prelude::*;
+use FinalProject::avlsummary::AVLSummaryTree;
+use FinalProject::rbsummary::RBSummaryTree;
+use FinalProject::summary::{Count, Max, Min, Sum, Summary};
+
+#[derive(Clone, Debug)]
+enum Op {
+    Insert(i32),
+    Remove(i32),
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        3 => (-100..100i32).prop_map(Op::Insert),
+        2 => (-100..100i32).prop_map(Op::Remove),
+    ]
+}
+
+// non-commutative summary: the keys in order, to catch a wrong combine order
+#[derive(Clone, Debug, PartialEq)]
+struct Keys(Vec<i32>);
+
+impl Summary<i32> for Keys {
+    fn identity() -> Self {
+        Keys(Vec::new())
+    }
+
+    fn of(key: &i32) -> Self {
+        Keys(vec![*key])
+    }
+
+    fn combine(&self, other: &Self) -> Self {
+        Keys(self.0.iter().chain(&other.0).copied().collect())
+    }
+}
+
+#[test]
+fn range_sums() {
+    let mut rb: RBSummaryTree<i64, Sum<i64>> = (1..=100).collect();
+    let mut avl: AVLSummaryTree<i64, Sum<i64>> = (1..=100).collect();
+    assert_eq!(rb.total(), Sum(5050));
+    assert_eq!(avl.total(), Sum(5050));
+    assert_eq!(rb.aggregate(11..=20), Sum(155));
+    assert_eq!(avl.aggregate(11..21), Sum(155));
+    assert_eq!(rb.aggregate(..=10), Sum(55));
+    assert_eq!(avl.aggregate(91..), Sum(955));
+    assert_eq!(rb.aggregate(50..50), Sum(0));
+
+    for key in (2..=100).step_by(2) {
+        assert!(rb.remove(&key));
+        assert!(avl.remove(&key));
+    }
+    assert!(!rb.remove(&2));
+    assert!(rb.validate().is_ok());
+    assert!(avl.validate().is_ok());
+    assert_eq!(rb.aggregate(1..=10), Sum(25));
+    assert_eq!(avl.aggregate(1..=10), Sum(25));
+    assert_eq!(rb.len(), 50);
+}
+
+#[test]
+fn min_max_and_count() {
+    let keys = [42, 7, 19, 88, -3, 61, 25];
+    let min: RBSummaryTree<i32, Min<i32>> = keys.into_iter().collect();
+    let max: AVLSummaryTree<i32, Max<i32>> = keys.into_iter().collect();
+    let count: RBSummaryTree<i32, Count> = keys.into_iter().collect();
+    assert_eq!(min.aggregate(10..), Min(Some(19)));
+    assert_eq!(min.aggregate(100..), Min(None));
+    assert_eq!(max.aggregate(..60), Max(Some(42)));
+    assert_eq!(max.total(), Max(Some(88)));
+    assert_eq!(count.aggregate(0..=50), Count(4));
+}
+
+proptest! {
+    #[test]
+    fn aggregates_match_a_fold(ops in prop::collection::vec(op(), 1..200), queries in prop::collection::vec((-110..110i32, -110..110i32), 10)) {
+        let mut rb: RBSummaryTree<i32, Keys> = RBSummaryTree::new();
+        let mut avl: AVLSummaryTree<i32, Keys> = AVLSummaryTree::new();
+        let mut model: Vec<i32> = Vec::new();
+        for op in &ops {
+            match *op {
+                Op::Insert(key) => {
+                    let fresh = !model.contains(&key);
+                    if fresh {
+                        model.push(key);
+                    }
+                    prop_assert_eq!(rb.insert(key), fresh);
+                    prop_assert_eq!(avl.insert(key), fresh);
+                },
+                Op::Remove(key) => {
+                    let present = model.contains(&key);
+                    model.retain(|&k| k != key);
+                    prop_assert_eq!(rb.remove(&key), present);
+                    prop_assert_eq!(avl.remove(&key), present);
+                },
+            }
+            if let Err(violation) = rb.validate() {
+                return Err(TestCaseError::fail(format!("red-black after {:?}: {}", op, violation)));
+            }
+            if let Err(violation) = avl.validate() {
+                return Err(TestCaseError::fail(format!("AVL after {:?}: {}", op, violation)));
+            }
+        }
+        model.sort();
+        prop_assert_eq!(rb.keys(), model.clone());
+        prop_assert_eq!(avl.total(), Keys(model.clone()));
+
+        for &(low, high) in &queries {
+            let expected = Keys(model.iter().copied().filter(|k| (low..high).contains(k)).collect());
+            prop_assert_eq!(rb.aggregate(low..high), expected.clone());
+            prop_assert_eq!(avl.aggregate(low..high), expected);
+            let range = (Bound::Excluded(low), Bound::Included(high));
+            let expected = Keys(model.iter().copied().filter(|&k| k > low && k <= high).collect());
+            prop_assert_eq!(rb.aggregate(range), expected.clone());
+            prop_assert_eq!(avl.aggregate(range), expected);
+        }
+    }
+}